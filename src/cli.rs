@@ -1,4 +1,10 @@
-use clap::Parser;
+use crate::core::install::SettingsScope;
+use crate::core::report::{ReportFormat, ReportGroup, ReportSource};
+use crate::core::sample::Scenario;
+use crate::core::theme::Theme;
+use crate::core::OutputMode;
+use chrono::NaiveDate;
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Parser, Debug)]
 #[command(name = "CCometixLine (ccline)")]
@@ -11,28 +17,163 @@ use clap::Parser;
 )]
 pub struct Cli {
     /// Configuration file path
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub config: Option<String>,
 
-    /// Theme selection
-    #[arg(short, long, default_value = "dark")]
-    pub theme: String,
+    /// Theme selection (overrides `theme` in the config file)
+    #[arg(short, long, value_enum, global = true)]
+    pub theme: Option<Theme>,
 
     /// Enable TUI configuration mode
     #[arg(long)]
     pub configure: bool,
 
-    /// Print default configuration
+    /// Print the effective configuration, each value annotated with the layer that set it
     #[arg(long)]
     pub print_config: bool,
 
+    /// With --print-config: print the built-in defaults only
+    #[arg(long, requires = "print_config", conflicts_with_all = ["effective", "template"])]
+    pub default: bool,
+
+    /// With --print-config: print defaults, config file and command line options merged (default)
+    #[arg(long, requires = "print_config", conflicts_with = "template")]
+    pub effective: bool,
+
+    /// With --print-config: print a commented template listing every option
+    #[arg(long, requires = "print_config")]
+    pub template: bool,
+
     /// Validate configuration file
     #[arg(long)]
     pub validate: bool,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputMode::Ansi, global = true)]
+    pub output: OutputMode,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Summarize cost, tokens and sessions by day, week, month, project or model
+    Report(ReportArgs),
+    /// Add the statusLine entry for this binary to Claude Code's settings.json
+    Install(InstallArgs),
+    /// Remove the ccline statusLine entry from Claude Code's settings.json
+    Uninstall(UninstallArgs),
+    /// Check config, git, terminal, settings.json, PackyCode, cache and segment timings
+    Doctor(DoctorArgs),
+    /// Render the statusline from a sample, a saved input or a named scenario without Claude Code
+    Preview(PreviewArgs),
+    /// Re-render recorded or saved statusline inputs with the current config
+    Replay(ReplayArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ReplayArgs {
+    /// Recording file (records.jsonl), or a file with one statusline JSON per line
+    pub file: String,
+
+    /// Only show entries whose output differs from the recording; exit with status 1 if any do
+    #[arg(long)]
+    pub diff: bool,
+
+    /// With --diff: compare plain text per segment, skipping segments that depend on the clock,
+    /// git or files other than the input (directory, git, cost, churn, cache, burn_rate, daily,
+    /// block, budget)
+    #[arg(long, requires = "diff")]
+    pub stable: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PreviewArgs {
+    /// Claude Code statusline JSON to render (defaults to a built-in sample)
+    #[arg(long, conflicts_with = "scenario")]
+    pub input: Option<String>,
+
+    /// Render a built-in scenario instead of the sample
+    #[arg(short, long, value_enum)]
+    pub scenario: Option<Scenario>,
+
+    /// Render every built-in theme, one per line
+    #[arg(long)]
+    pub all_themes: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Claude Code statusline JSON used to time segments (defaults to a built-in sample)
+    #[arg(long)]
+    pub input: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct InstallArgs {
+    /// Settings file to patch: user (~/.claude), project (.claude/settings.json) or local (.claude/settings.local.json)
+    #[arg(short, long, value_enum, default_value_t = SettingsScope::User)]
+    pub scope: SettingsScope,
+
+    /// Print the resulting settings without writing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Command for the statusLine entry (defaults to the path of this binary)
+    #[arg(long)]
+    pub command: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct UninstallArgs {
+    /// Settings file to patch: user (~/.claude), project (.claude/settings.json) or local (.claude/settings.local.json)
+    #[arg(short, long, value_enum, default_value_t = SettingsScope::User)]
+    pub scope: SettingsScope,
+
+    /// Print the resulting settings without writing them
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Remove the statusLine entry even if it does not run ccline
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    /// Group rows by
+    #[arg(short, long, value_enum, default_value_t = ReportGroup::Day)]
+    pub by: ReportGroup,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = ReportFormat::Table)]
+    pub format: ReportFormat,
+
+    /// Read recorded session history or scan Claude Code transcripts
+    #[arg(short, long, value_enum, default_value_t = ReportSource::History)]
+    pub source: ReportSource,
+
+    /// Only include usage on or after this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub since: Option<NaiveDate>,
+
+    /// Only include usage on or before this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub until: Option<NaiveDate>,
 }
 
 impl Cli {
     pub fn parse_args() -> Self {
         Self::parse()
     }
+
+    /// 命令行参数对应的配置项，叠加在配置文件之上
+    pub fn overrides(&self) -> toml::Table {
+        let mut table = toml::Table::new();
+        if let Some(theme) = self.theme {
+            table.insert("theme".to_string(), theme.name().into());
+        }
+        table
+    }
 }
@@ -1,27 +1,136 @@
-use super::types::{Config, SegmentsConfig};
-
-pub const DEFAULT_CONFIG: Config = Config {
-    theme: String::new(), // Set to "dark" at runtime
-    segments: SegmentsConfig {
-        directory: true,
-        git: true,
-        model: true,
-        usage: true,
-        cost: true,
-    },
+use super::types::{
+    BarStyle, BlockConfig, BudgetConfig, BudgetSource, BurnRateConfig, ChurnConfig, Config,
+    CostConfig, DailyConfig, DirectoryConfig, DirectoryStyle, HistoryConfig, HyperlinkMode,
+    IconMode, RecordConfig, SegmentsConfig, UsageColors, UsageConfig,
 };
+use crate::core::output::Color;
+use std::collections::BTreeMap;
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             theme: "dark".to_string(),
-            segments: SegmentsConfig {
-                directory: true,
-                git: true,
-                model: true,
-                usage: true,
-                cost: true,
-            },
+            icons: IconMode::Unicode,
+            segments: SegmentsConfig::default(),
+            hyperlinks: HyperlinkMode::Auto,
+            directory: DirectoryConfig::default(),
+            models: Vec::new(),
+            cost: CostConfig::default(),
+            churn: ChurnConfig::default(),
+            burn_rate: BurnRateConfig::default(),
+            usage: UsageConfig::default(),
+            history: HistoryConfig::default(),
+            daily: DailyConfig::default(),
+            budget: BudgetConfig::default(),
+            block: BlockConfig::default(),
+            record: RecordConfig::default(),
+        }
+    }
+}
+
+impl Default for SegmentsConfig {
+    fn default() -> Self {
+        SegmentsConfig {
+            directory: true,
+            git: true,
+            model: true,
+            usage: true,
+            cost: true,
+            churn: true,
+            burn_rate: false,
+            session: false,
+            cache: false,
+            daily: false,
+            budget: false,
+            block: false,
+            order: Vec::new(),
+        }
+    }
+}
+
+impl Default for DirectoryConfig {
+    fn default() -> Self {
+        DirectoryConfig {
+            style: DirectoryStyle::Name,
+            max_depth: 0,
+            project: false,
+        }
+    }
+}
+
+impl Default for BurnRateConfig {
+    fn default() -> Self {
+        BurnRateConfig {
+            projection_hours: 5.0,
+            warn_cost_per_hour: None,
+            critical_cost_per_hour: None,
+        }
+    }
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        BudgetConfig {
+            session: None,
+            daily: None,
+            weekly: None,
+            monthly: None,
+            warning_percent: 80.0,
+            critical_percent: 100.0,
+            source: BudgetSource::Local,
+            command: None,
+            quota_ttl_secs: 60,
+        }
+    }
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            enabled: true,
+            path: None,
+        }
+    }
+}
+
+impl Default for RecordConfig {
+    fn default() -> Self {
+        RecordConfig {
+            enabled: false,
+            path: None,
+            max_bytes: 1024 * 1024,
+            max_files: 3,
+        }
+    }
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        UsageConfig {
+            warning: 50.0,
+            high: 75.0,
+            critical: 90.0,
+            colors: UsageColors::default(),
+            auto_compact_percent: 80.0,
+            compact_warning_margin: 10.0,
+            bar_style: BarStyle::Blocks,
+            bar_width: 10,
+            precision: 2,
+            show_breakdown: false,
+            show_output_tokens: false,
+            context_windows: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for UsageColors {
+    fn default() -> Self {
+        UsageColors {
+            // 薰衣草色
+            normal: Color::Fixed(147),
+            warning: Color::Yellow,
+            high: Color::Fixed(208),
+            critical: Color::Red,
         }
     }
 }
@@ -0,0 +1,174 @@
+use super::loader::ConfigLoader;
+use super::types::Config;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+use toml::{Table, Value};
+use toml_edit::{DocumentMut, Item};
+
+/// 配置值的来源，后面的层覆盖前面的层
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigLayer {
+    Default,
+    File,
+    Cli,
+}
+
+impl ConfigLayer {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConfigLayer::Default => "default",
+            ConfigLayer::File => "file",
+            ConfigLayer::Cli => "cli",
+        }
+    }
+}
+
+/// 依次叠加默认值、配置文件和命令行参数后的配置，记录每层设置了哪些键
+pub struct LayeredConfig {
+    pub config: Config,
+    /// 实际读取的配置文件，没有配置文件时为 None
+    pub path: Option<PathBuf>,
+    file: Table,
+    cli: Table,
+}
+
+impl LayeredConfig {
+    /// path 为 None 时读取默认配置文件（不存在时只使用默认值），无法读取或解析时返回错误
+    pub fn load(path: Option<&str>, cli: Table) -> Result<Self, Box<dyn Error>> {
+        let path = match path {
+            Some(path) => Some(PathBuf::from(path)),
+            None => ConfigLoader::config_path().filter(|path| path.exists()),
+        };
+        let file: Table = match &path {
+            Some(path) => toml::from_str(&fs::read_to_string(path)?)?,
+            None => Table::new(),
+        };
+        let config = merged(&file, &cli).try_into()?;
+        Ok(Self {
+            config,
+            path,
+            file,
+            cli,
+        })
+    }
+
+    /// 忽略配置文件，只叠加默认值和命令行参数
+    pub fn without_file(cli: Table) -> Self {
+        let config = merged(&Table::new(), &cli).try_into().unwrap_or_default();
+        Self {
+            config,
+            path: None,
+            file: Table::new(),
+            cli,
+        }
+    }
+
+    /// 点分路径对应的值由哪一层设置
+    pub fn source(&self, path: &[String]) -> ConfigLayer {
+        if lookup(&self.cli, path) {
+            ConfigLayer::Cli
+        } else if lookup(&self.file, path) {
+            ConfigLayer::File
+        } else {
+            ConfigLayer::Default
+        }
+    }
+
+    /// 生效的完整配置，每个值后面注释其来源
+    pub fn annotated(&self) -> Result<String, Box<dyn Error>> {
+        let mut doc: DocumentMut = toml::to_string(&self.config)?.parse()?;
+        self.annotate(doc.as_table_mut(), &mut Vec::new());
+
+        let file = self
+            .path
+            .as_ref()
+            .map_or("(no config file)".to_string(), |p| p.display().to_string());
+        let mut header = format!(
+            "# Effective configuration. Each value is followed by the layer that set it:\n\
+             #   default  built-in default\n\
+             #   file     {}\n\
+             #   cli      command line options\n\
+             # Unset options are omitted; `ccline --print-config --template` lists them all.\n",
+            file
+        );
+
+        // serde 会静默忽略未知的键，拼写错误时这里能看出来
+        let effective = Value::try_from(&self.config)?;
+        let mut unknown = Vec::new();
+        unknown_keys(&self.file, &effective, &mut Vec::new(), &mut unknown);
+        if !unknown.is_empty() {
+            header.push_str(&format!(
+                "# Ignored unknown keys in the config file: {}\n",
+                unknown.join(", ")
+            ));
+        }
+        Ok(format!("{}\n{}", header, doc))
+    }
+
+    fn annotate(&self, table: &mut toml_edit::Table, path: &mut Vec<String>) {
+        for (key, item) in table.iter_mut() {
+            path.push(key.get().to_string());
+            let label = self.source(path).label();
+            match item {
+                Item::Value(value) => value.decor_mut().set_suffix(format!("  # {}", label)),
+                Item::Table(table) => self.annotate(table, path),
+                Item::ArrayOfTables(array) => {
+                    for table in array.iter_mut() {
+                        table.decor_mut().set_suffix(format!("  # {}", label));
+                    }
+                }
+                Item::None => {}
+            }
+            path.pop();
+        }
+    }
+}
+
+/// 深度合并，overlay 中的表逐键覆盖 base，其他值整体替换
+fn merged(base: &Table, overlay: &Table) -> Value {
+    let mut result = base.clone();
+    for (key, value) in overlay {
+        match (result.get_mut(key), value) {
+            (Some(Value::Table(existing)), Value::Table(table)) => {
+                if let Value::Table(table) = merged(existing, table) {
+                    *existing = table;
+                }
+            }
+            _ => {
+                result.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Value::Table(result)
+}
+
+fn lookup(table: &Table, path: &[String]) -> bool {
+    let Some((first, rest)) = path.split_first() else {
+        return false;
+    };
+    match (table.get(first), rest.is_empty()) {
+        (Some(_), true) => true,
+        (Some(Value::Table(table)), false) => lookup(table, rest),
+        _ => false,
+    }
+}
+
+fn unknown_keys(
+    file: &Table,
+    effective: &Value,
+    path: &mut Vec<String>,
+    unknown: &mut Vec<String>,
+) {
+    for (key, value) in file {
+        path.push(key.clone());
+        match (effective.get(key), value) {
+            (None, _) => unknown.push(path.join(".")),
+            (Some(inner @ Value::Table(_)), Value::Table(table)) => {
+                unknown_keys(table, inner, path, unknown)
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+}
@@ -1,16 +1,30 @@
 use super::types::Config;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 pub struct ConfigLoader;
 
 impl ConfigLoader {
     pub fn load() -> Config {
-        // Return default config for now, implement multi-layer loading later
-        Config::default()
+        // 配置文件缺失或解析失败时回退到默认配置，statusline 不应因此中断
+        Self::config_path()
+            .filter(|path| path.exists())
+            .and_then(|path| Self::load_from_path(path).ok())
+            .unwrap_or_default()
     }
 
-    pub fn load_from_path<P: AsRef<Path>>(_path: P) -> Result<Config, Box<dyn std::error::Error>> {
-        // Load config from file
-        Ok(Config::default())
+    pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<Config, Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// 默认配置文件路径：~/.claude/ccline/config.toml
+    pub fn config_path() -> Option<PathBuf> {
+        Self::ccline_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    /// ccline 的数据目录：~/.claude/ccline
+    pub fn ccline_dir() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".claude").join("ccline"))
     }
 }
@@ -1,7 +1,9 @@
 pub mod defaults;
+pub mod layers;
 pub mod loader;
+pub mod template;
 pub mod types;
 
-pub use defaults::DEFAULT_CONFIG;
+pub use layers::{ConfigLayer, LayeredConfig};
 pub use loader::ConfigLoader;
 pub use types::*;
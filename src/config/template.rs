@@ -0,0 +1,380 @@
+use super::types::Config;
+use toml::{Table, Value};
+
+/// 模板中的一个配置项：键名、说明，以及默认值为空时使用的示例
+struct Entry {
+    key: &'static str,
+    doc: &'static str,
+    example: Option<&'static str>,
+}
+
+const fn entry(key: &'static str, doc: &'static str) -> Entry {
+    Entry {
+        key,
+        doc,
+        example: None,
+    }
+}
+
+const fn example(key: &'static str, doc: &'static str, example: &'static str) -> Entry {
+    Entry {
+        key,
+        doc,
+        example: Some(example),
+    }
+}
+
+/// 按表分组的配置项，顺序与 Config 的字段一致；"" 为顶层键
+const SECTIONS: &[(&str, &[Entry])] = &[
+    (
+        "",
+        &[
+            entry("theme", "Built-in theme: \"dark\", \"light\" or \"mono\""),
+            entry(
+                "icons",
+                "Icon style: \"unicode\", \"nerd\", \"emoji\", \"ascii\" or \"none\"",
+            ),
+            entry(
+                "hyperlinks",
+                "OSC 8 hyperlinks: \"auto\" (by terminal and output mode), \"always\" or \"never\"",
+            ),
+            // 数组表必须在顶层键之后，否则取消注释后会吞掉后面的键
+            example(
+                "models",
+                "Custom models, matched before the built-in table by model id or display name (* wildcards)",
+                "[[models]]\npatterns = [\"my-model-*\"]\nname = \"Mine\"\nfamily = \"custom\"\nicon = \"◆\"\ncolor = \"magenta\"\ncontext_window = 200000\npricing = { input = 3.0, output = 15.0, cache_write = 3.75, cache_read = 0.3 }",
+            ),
+        ],
+    ),
+    (
+        "segments",
+        &[
+            entry("directory", "Current directory"),
+            entry("git", "Git branch and status"),
+            entry("model", "Model name"),
+            entry("usage", "Context window usage"),
+            entry("cost", "Session cost"),
+            entry("churn", "Lines added and removed in this session"),
+            entry("burn_rate", "Cost per hour and projected session cost"),
+            entry("session", "Session duration and API time"),
+            entry("cache", "Prompt cache hit rate"),
+            entry("daily", "Cost of all sessions today"),
+            entry("budget", "Spending against the budgets in [budget]"),
+            entry("block", "Usage in the current 5-hour billing block"),
+            example(
+                "order",
+                "Display order by segment name; unlisted segments follow in the default order",
+                "[\"model\", \"directory\", \"git\", \"usage\"]",
+            ),
+        ],
+    ),
+    (
+        "directory",
+        &[
+            entry(
+                "style",
+                "\"name\" (last component), \"full\" (~ for home), \"repo\" (relative to the git root) or \"fish\" (abbreviated)",
+            ),
+            entry("max_depth", "Maximum number of path components shown, 0 for no limit"),
+            entry(
+                "project",
+                "Look up Cargo.toml, package.json and similar manifests to show the project name and language",
+            ),
+        ],
+    ),
+    (
+        "cost",
+        &[
+            entry(
+                "breakdown",
+                "Split the cost into input, output, cache write and cache read, and by model when the session used several",
+            ),
+            entry(
+                "verify",
+                "Also show the cost computed from the transcript, to check the reported cost",
+            ),
+            example(
+                "provider",
+                "Provider name; matched against ANTHROPIC_BASE_URL when unset",
+                "\"packycode\"",
+            ),
+            example(
+                "providers",
+                "Price multipliers of third-party providers",
+                "[[cost.providers]]\nname = \"packycode\"\nbase_url = \"packycode.com\"\nmultiplier = 0.5",
+            ),
+        ],
+    ),
+    (
+        "churn",
+        &[
+            entry("net", "Show net lines (added - removed)"),
+            entry("cost_per_100_lines", "Show the cost per 100 changed lines"),
+            entry(
+                "git_diff",
+                "Show git diff line counts of the working tree against HEAD",
+            ),
+        ],
+    ),
+    (
+        "burn_rate",
+        &[
+            entry(
+                "projection_hours",
+                "Window (hours) used to project the total cost: the session is projected to the next multiple of it",
+            ),
+            example(
+                "warn_cost_per_hour",
+                "Cost per hour (USD) shown in the warning color",
+                "5.0",
+            ),
+            example(
+                "critical_cost_per_hour",
+                "Cost per hour (USD) shown in the critical color",
+                "10.0",
+            ),
+        ],
+    ),
+    (
+        "usage",
+        &[
+            entry("warning", "Context usage (percent) shown in the warning color"),
+            entry("high", "Context usage (percent) shown in the high color"),
+            entry("critical", "Context usage (percent) shown in the critical color"),
+            entry(
+                "auto_compact_percent",
+                "Context usage (percent) at which Claude Code auto-compacts",
+            ),
+            entry(
+                "compact_warning_margin",
+                "Warn with the remaining tokens when this close (percent) to auto-compact",
+            ),
+            entry(
+                "bar_style",
+                "\"blocks\", \"eighths\", \"braille\", \"dots\", \"percent\" or \"numeric\"",
+            ),
+            entry("bar_width", "Progress bar width in characters"),
+            entry("precision", "Decimal places of the percentage"),
+            entry(
+                "show_breakdown",
+                "Split tokens into new input, cache read and cache write",
+            ),
+            entry("show_output_tokens", "Show output tokens of the last turn"),
+        ],
+    ),
+    (
+        "usage.colors",
+        &[
+            entry("normal", "Color names or 0-255 palette indexes"),
+            entry("warning", ""),
+            entry("high", ""),
+            entry("critical", ""),
+        ],
+    ),
+    (
+        "usage.context_windows",
+        &[example(
+            "\"claude-sonnet-4-5*\"",
+            "Context window size by model id (* wildcards), used when Claude Code does not report one",
+            "1000000",
+        )],
+    ),
+    (
+        "history",
+        &[
+            entry("enabled", "Record sessions for `ccline report`"),
+            example(
+                "path",
+                "History file, defaults to ~/.claude/ccline/history.jsonl",
+                "\"/var/log/ccline/history.jsonl\"",
+            ),
+        ],
+    ),
+    (
+        "daily",
+        &[
+            entry(
+                "day_start_hour",
+                "Hour (0-23) the day starts; earlier usage counts towards the previous day",
+            ),
+            example(
+                "timezone",
+                "\"utc\" or a fixed offset such as \"+08:00\"; local time when unset",
+                "\"+08:00\"",
+            ),
+        ],
+    ),
+    (
+        "budget",
+        &[
+            example("session", "Budgets in USD; unset periods are not checked", "5.0"),
+            example("daily", "", "20.0"),
+            example("weekly", "", "100.0"),
+            example("monthly", "", "300.0"),
+            entry("warning_percent", "Percent of a budget shown in the warning color"),
+            entry(
+                "critical_percent",
+                "Percent of a budget shown in the critical color",
+            ),
+            entry(
+                "source",
+                "\"local\" (sessions and transcripts) or \"packycode\" (account quota)",
+            ),
+            example(
+                "command",
+                "Run once when a budget reaches warning or critical, with CCLINE_BUDGET_* variables set",
+                "\"notify-send \\\"ccline\\\" \\\"$CCLINE_BUDGET_LEVEL\\\"\"",
+            ),
+            entry("quota_ttl_secs", "Seconds to cache the PackyCode quota"),
+        ],
+    ),
+    (
+        "block",
+        &[
+            example(
+                "token_limit",
+                "Warn when the projected usage at the end of the 5-hour block exceeds these limits",
+                "10000000",
+            ),
+            example("cost_limit", "", "50.0"),
+        ],
+    ),
+    (
+        "record",
+        &[
+            entry(
+                "enabled",
+                "Record every input and output for `ccline replay`",
+            ),
+            example(
+                "path",
+                "Record file, defaults to ~/.claude/ccline/records.jsonl",
+                "\"/var/log/ccline/records.jsonl\"",
+            ),
+            entry("max_bytes", "Rotate the record file when it exceeds this size"),
+            entry("max_files", "Number of rotated files kept"),
+        ],
+    ),
+];
+
+/// 生成带注释的完整配置模板，所有键都被注释掉并填入默认值或示例
+pub fn template() -> String {
+    render(true)
+}
+
+/// commented 为 false 时只保留说明注释，测试用它检查模板取消注释后能否解析
+fn render(commented: bool) -> String {
+    let prefix = if commented { "# " } else { "" };
+    let defaults = Value::try_from(Config::default()).unwrap_or(Value::Table(Table::new()));
+    let mut out = String::from(
+        "# CCometixLine configuration (~/.claude/ccline/config.toml)\n\
+         # Every option is listed with its default value; uncomment a line to change it.\n",
+    );
+
+    for (table, entries) in SECTIONS {
+        let section = table
+            .split('.')
+            .filter(|part| !part.is_empty())
+            .try_fold(&defaults, |value, part| value.get(part));
+        out.push('\n');
+        if !table.is_empty() {
+            out.push_str(&format!("[{}]\n", table));
+        }
+
+        for entry in entries.iter() {
+            let default = section.and_then(|section| section.get(entry.key));
+            let line = match (default, entry.example) {
+                (_, Some(example)) if example.starts_with("[[") => example.to_string(),
+                // 空数组和未设置的值使用示例
+                (Some(Value::Array(array)), Some(example)) if array.is_empty() => {
+                    format!("{} = {}", entry.key, example)
+                }
+                (Some(value), _) => format!("{} = {}", entry.key, value),
+                (None, Some(example)) => format!("{} = {}", entry.key, example),
+                (None, None) => continue,
+            };
+            if !entry.doc.is_empty() {
+                out.push_str(&format!("# {}\n", entry.doc));
+            }
+            for line in line.lines() {
+                out.push_str(&format!("{}{}\n", prefix, line));
+            }
+        }
+
+        // 新增的配置项即使忘了写说明也会出现在模板中
+        if let Some(Value::Table(section)) = section {
+            for (key, value) in section {
+                if !value.is_table() && !entries.iter().any(|entry| entry.key == key) {
+                    out.push_str(&format!("{}{} = {}\n", prefix, key, value));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, template, SECTIONS};
+    use crate::config::Config;
+    use toml::{Table, Value};
+
+    /// 默认配置中每个表的路径和其中的非表键
+    fn default_keys(table: &Table, path: &str, keys: &mut Vec<(String, String)>) {
+        for (key, value) in table {
+            match value {
+                Value::Table(child) if path.is_empty() => default_keys(child, key, keys),
+                Value::Table(child) => default_keys(child, &format!("{}.{}", path, key), keys),
+                _ => keys.push((path.to_string(), key.clone())),
+            }
+        }
+    }
+
+    #[test]
+    fn every_config_key_is_documented() {
+        let Value::Table(defaults) = Value::try_from(Config::default()).unwrap() else {
+            panic!("Config does not serialize to a table");
+        };
+        let mut keys = Vec::new();
+        default_keys(&defaults, "", &mut keys);
+
+        let missing: Vec<String> = keys
+            .into_iter()
+            .filter(|(path, key)| {
+                !SECTIONS.iter().any(|(table, entries)| {
+                    table == path && entries.iter().any(|entry| entry.key == key)
+                })
+            })
+            .map(|(path, key)| format!("{}.{}", path, key))
+            .collect();
+        assert!(missing.is_empty(), "undocumented keys: {:?}", missing);
+    }
+
+    #[test]
+    fn template_parses_to_defaults() {
+        let config: Config = toml::from_str(&template()).unwrap();
+        assert_eq!(
+            Value::try_from(config).unwrap(),
+            Value::try_from(Config::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn uncommented_template_parses() {
+        let content = render(false);
+        let table: Table = toml::from_str(&content).unwrap();
+        let config: Config = toml::from_str(&content).unwrap();
+
+        // 示例中的键都能被 Config 识别，没有拼写错误被 serde 忽略
+        let Value::Table(parsed) = Value::try_from(config).unwrap() else {
+            panic!("Config does not serialize to a table");
+        };
+        let mut written = Vec::new();
+        default_keys(&table, "", &mut written);
+        let mut known = Vec::new();
+        default_keys(&parsed, "", &mut known);
+        let unknown: Vec<_> = written.iter().filter(|key| !known.contains(key)).collect();
+        assert!(unknown.is_empty(), "unknown keys: {:?}", unknown);
+    }
+}
@@ -1,22 +1,292 @@
+use crate::core::output::Color;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct Config {
     pub theme: String,
+    /// 模型、目录、Git 和上下文 segment 使用的图标
+    pub icons: IconMode,
     pub segments: SegmentsConfig,
+    pub hyperlinks: HyperlinkMode,
+    pub directory: DirectoryConfig,
+    /// 用户自定义模型，优先于内置模型表匹配
+    pub models: Vec<ModelSpec>,
+    pub cost: CostConfig,
+    pub churn: ChurnConfig,
+    pub burn_rate: BurnRateConfig,
+    pub usage: UsageConfig,
+    pub history: HistoryConfig,
+    pub daily: DailyConfig,
+    pub budget: BudgetConfig,
+    pub block: BlockConfig,
+    pub record: RecordConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
 pub struct SegmentsConfig {
     pub directory: bool,
     pub git: bool,
     pub model: bool,
     pub usage: bool,
     pub cost: bool,
+    pub churn: bool,
+    pub burn_rate: bool,
+    pub session: bool,
+    pub cache: bool,
+    pub daily: bool,
+    pub budget: bool,
+    pub block: bool,
+    /// 显示顺序（segment 名称），未列出的 segment 按默认顺序排在后面
+    pub order: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DirectoryConfig {
+    pub style: DirectoryStyle,
+    /// 最多显示的路径层级，0 表示不限制
+    pub max_depth: usize,
+    /// 向上查找 Cargo.toml、package.json 等清单，显示项目名和语言图标
+    pub project: bool,
+}
+
+/// 图标风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconMode {
+    /// 通用 Unicode 符号（● ○ ◐），不需要特殊字体
+    #[default]
+    Unicode,
+    /// Nerd Font 图标
+    Nerd,
+    Emoji,
+    /// 纯 ASCII，如 [M]
+    Ascii,
+    /// 不显示图标
+    None,
+}
+
+/// 目录显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DirectoryStyle {
+    /// 仅显示当前目录名
+    Name,
+    /// 完整路径，home 目录替换为 ~
+    Full,
+    /// 相对于 Git 仓库根目录的路径，如 repo/sub/dir
+    Repo,
+    /// fish 风格缩写，如 ~/w/p/packycc
+    Fish,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CostConfig {
+    /// 按输入、输出、缓存写入、缓存读取拆分显示费用
+    pub breakdown: bool,
+    /// 显示根据 transcript 计算出的费用，用于核对 Claude Code 报告的费用
+    pub verify: bool,
+    /// 指定服务商名称，未设置时根据 ANTHROPIC_BASE_URL 自动匹配
+    pub provider: Option<String>,
+    pub providers: Vec<ProviderConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    /// 上下文使用率阈值（百分比），超过后依次切换到对应颜色
+    pub warning: f64,
+    pub high: f64,
+    pub critical: f64,
+    pub colors: UsageColors,
+    /// Claude Code 自动压缩上下文时的使用率（百分比），随版本不同可调整
+    pub auto_compact_percent: f64,
+    /// 距离自动压缩不足该百分比时显示警告图标和剩余 token 数
+    pub compact_warning_margin: f64,
+    pub bar_style: BarStyle,
+    /// 进度条宽度（字符数）
+    pub bar_width: usize,
+    /// 百分比的小数位数
+    pub precision: usize,
+    /// 显示新输入、缓存读取、缓存写入的 token 拆分
+    pub show_breakdown: bool,
+    /// 显示上一轮的输出 token 数
+    pub show_output_tokens: bool,
+    /// 按 model id（支持 * 通配符）覆盖上下文窗口大小，仅在 Claude Code 未报告时使用
+    pub context_windows: BTreeMap<String, u32>,
+}
+
+/// 上下文使用率的显示样式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BarStyle {
+    /// ▓▓▓░░░ 整格进度条
+    Blocks,
+    /// ███▌░░ 八分之一精度的平滑进度条
+    Eighths,
+    /// ⣿⣿⣧⣀⣀ 盲文点阵进度条
+    Braille,
+    /// ●●●○○○ 圆点
+    Dots,
+    /// 只显示百分比
+    Percent,
+    /// 只显示 token 数（已用/总量）
+    Numeric,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UsageColors {
+    pub normal: Color,
+    pub warning: Color,
+    pub high: Color,
+    pub critical: Color,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ChurnConfig {
+    /// 显示净增行数（增加 - 删除）
+    pub net: bool,
+    /// 显示每改动 100 行的费用
+    pub cost_per_100_lines: bool,
+    /// 显示工作区相对 HEAD 的 git diff 行数，便于与会话统计对比
+    pub git_diff: bool,
+}
+
+/// 当天费用统计，扫描本地所有 transcript
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DailyConfig {
+    /// 一天从几点开始（0-23），如 4 表示凌晨 4 点前的用量计入前一天
+    pub day_start_hour: u32,
+    /// "utc" 或 "+08:00" 形式的固定时区，未设置时使用本地时区
+    pub timezone: Option<String>,
+}
+
+/// 5 小时计费块，推算的块结束时用量超过限额时提示
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BlockConfig {
+    pub token_limit: Option<u64>,
+    pub cost_limit: Option<f64>,
+}
+
+/// 预算的已用金额来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetSource {
+    /// 会话费用和本地 transcript
+    Local,
+    /// PackyCode 账户的日/月额度
+    Packycode,
+}
+
+/// 费用预算（美元），未设置的周期不检查
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BudgetConfig {
+    pub session: Option<f64>,
+    pub daily: Option<f64>,
+    pub weekly: Option<f64>,
+    pub monthly: Option<f64>,
+    /// 达到预算的百分之多少时显示警告色
+    pub warning_percent: f64,
+    /// 达到预算的百分之多少时显示严重警告色
+    pub critical_percent: f64,
+    pub source: BudgetSource,
+    /// 进入 warning / critical 时执行一次的命令，预算信息通过 CCLINE_BUDGET_* 环境变量传递
+    pub command: Option<String>,
+    /// PackyCode 额度的缓存时间（秒）
+    pub quota_ttl_secs: u64,
+}
+
+/// 会话历史记录，供用量报告使用
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    /// 历史文件路径，默认为 ~/.claude/ccline/history.jsonl
+    pub path: Option<String>,
+}
+
+/// 记录每次收到的输入和渲染结果，用于排查问题和回放
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RecordConfig {
+    pub enabled: bool,
+    /// 记录文件路径，默认为 ~/.claude/ccline/records.jsonl
+    pub path: Option<String>,
+    /// 文件超过该大小（字节）时轮转为 records.jsonl.1
+    pub max_bytes: u64,
+    /// 最多保留的轮转文件数
+    pub max_files: u32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BurnRateConfig {
+    /// 按当前速率推算会话到下一个该时长整数倍时的总费用（小时）
+    pub projection_hours: f64,
+    /// 每小时费用超过该值时显示警告色
+    pub warn_cost_per_hour: Option<f64>,
+    /// 每小时费用超过该值时显示严重警告色
+    pub critical_cost_per_hour: Option<f64>,
+}
+
+/// 第三方服务商（如 PackyCode）的价格倍率
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ProviderConfig {
+    pub name: String,
+    /// 与 ANTHROPIC_BASE_URL 做子串匹配
+    #[serde(default)]
+    pub base_url: Option<String>,
+    pub multiplier: f64,
+}
+
+/// 模型注册表条目，patterns 支持 * 通配符，不区分大小写地匹配 model id 或 display_name
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ModelSpec {
+    pub patterns: Vec<String>,
+    /// 状态栏中显示的简短名称
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<Pricing>,
+}
+
+/// 每百万 token 的美元价格
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Pricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+/// OSC 8 超链接开关，auto 时根据终端和输出模式自动判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HyperlinkMode {
+    Auto,
+    Always,
+    Never,
 }
 
 // Claude Code 传递的 cost 数据
 #[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct Cost {
     pub total_cost_usd: Option<f64>,
     pub total_duration_ms: Option<u64>,
@@ -27,12 +297,21 @@ pub struct Cost {
 
 // Claude Code 传递的上下文窗口数据
 #[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct ContextWindow {
+    /// 会话累计的输入 / 输出 token 数
+    pub total_input_tokens: Option<u64>,
+    pub total_output_tokens: Option<u64>,
     pub context_window_size: Option<u32>,
+    /// 上下文使用率（百分比），旧版本 Claude Code 不传递
+    pub used_percentage: Option<f64>,
+    pub remaining_percentage: Option<f64>,
     pub current_usage: Option<CurrentUsage>,
 }
 
+// 上一轮请求的 token 用量，缺少的字段按 0 处理
 #[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct CurrentUsage {
     pub input_tokens: u32,
     pub output_tokens: u32,
@@ -40,22 +319,63 @@ pub struct CurrentUsage {
     pub cache_read_input_tokens: u32,
 }
 
-// Claude Code 传入的数据结构
-#[derive(Deserialize)]
+// Claude Code 传入的数据结构。所有结构都带 #[serde(default)]，任何字段缺失都能解析：
+// model 和 workspace 缺失时为空值（显示名为空、current_dir 回退到 cwd），其余字段为 Option
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct Model {
+    /// 稳定的模型 id，如 claude-sonnet-4-5-20250929
+    pub id: Option<String>,
     pub display_name: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct Workspace {
     pub current_dir: String,
+    pub project_dir: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct OutputStyle {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
 pub struct InputData {
+    pub session_id: Option<String>,
+    pub transcript_path: Option<String>,
+    pub cwd: Option<String>,
     pub model: Model,
     pub workspace: Workspace,
-    pub transcript_path: String,
+    pub version: Option<String>,
+    pub output_style: Option<OutputStyle>,
     pub cost: Option<Cost>,
     pub context_window: Option<ContextWindow>,
+    pub exceeds_200k_tokens: Option<bool>,
+    /// 未识别的字段，供自定义 segment 读取
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl InputData {
+    /// 当前工作目录，workspace.current_dir 缺失时回退到 cwd
+    pub fn current_dir(&self) -> &str {
+        if !self.workspace.current_dir.is_empty() {
+            return &self.workspace.current_dir;
+        }
+        self.cwd.as_deref().unwrap_or(".")
+    }
+
+    /// 按点分路径读取未识别的字段，如 "agent.name"
+    pub fn extra_field(&self, path: &str) -> Option<&serde_json::Value> {
+        let mut parts = path.split('.');
+        let mut value = self.extra.get(parts.next()?)?;
+        for part in parts {
+            value = value.get(part)?;
+        }
+        Some(value)
+    }
 }
@@ -0,0 +1,57 @@
+use crate::core::index::{IndexedEntry, SharedEntries};
+use crate::core::pricing::{CostBreakdown, CostEngine};
+use crate::core::transcript::TokenUsage;
+
+/// 订阅额度按 5 小时滚动窗口重置
+pub const BLOCK_SECS: i64 = 5 * 3600;
+/// 往前多看两个块的时间，保证当前块的开始时间能正确推算
+pub const LOOKBACK_SECS: i64 = 3 * BLOCK_SECS;
+
+/// 一个 5 小时计费块
+#[derive(Debug, Clone, Default)]
+pub struct Block {
+    /// 块的开始时间（第一条记录所在的整点，Unix 秒）
+    pub start: i64,
+    /// 最后一条记录的时间
+    pub last_activity: i64,
+    pub usage: TokenUsage,
+    pub cost: f64,
+}
+
+impl Block {
+    pub fn end(&self) -> i64 {
+        self.start + BLOCK_SECS
+    }
+}
+
+/// 把按时间排序的记录切分为计费块：距块开始超过 5 小时，或与上一条记录间隔超过 5 小时时开始新块
+pub fn split_blocks(entries: &[IndexedEntry], engine: &CostEngine) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    for entry in entries {
+        let starts_new = blocks.last().is_none_or(|block| {
+            entry.time >= block.end() || entry.time - block.last_activity >= BLOCK_SECS
+        });
+        if starts_new {
+            blocks.push(Block {
+                start: entry.time - entry.time.rem_euclid(3600),
+                ..Default::default()
+            });
+        }
+
+        let block = blocks.last_mut().expect("block was just pushed");
+        block.last_activity = entry.time;
+        block.usage.add(&entry.usage);
+        if let Some(pricing) = engine.pricing_for(&entry.model) {
+            block.cost +=
+                CostBreakdown::from_usage(&entry.usage, &pricing).total() * engine.multiplier();
+        }
+    }
+    blocks
+}
+
+/// 当前仍在进行中的计费块，最近 5 小时没有用量时返回 None
+pub fn active_block(usage: &SharedEntries, now: i64, engine: &CostEngine) -> Option<Block> {
+    split_blocks(usage.since(now - LOOKBACK_SECS), engine)
+        .pop()
+        .filter(|block| now < block.end() && now - block.last_activity < BLOCK_SECS)
+}
@@ -0,0 +1,409 @@
+use crate::config::{BudgetConfig, BudgetSource, Config, InputData};
+use crate::core::cache;
+use crate::core::daily;
+use crate::core::index::SharedEntries;
+use crate::core::models::ModelRegistry;
+use crate::core::pricing::CostEngine;
+use crate::core::quota;
+use crate::core::transcript;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+
+/// 已触发告警的记录，每个周期的每个级别只执行一次命令
+const ALERTS_FILE: &str = "budget_alerts.json";
+const ALERTS_LIMIT: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetLevel {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl BudgetLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetLevel::Normal => "normal",
+            BudgetLevel::Warning => "warning",
+            BudgetLevel::Critical => "critical",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetPeriod {
+    Session,
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl BudgetPeriod {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Session => "session",
+            BudgetPeriod::Daily => "day",
+            BudgetPeriod::Weekly => "week",
+            BudgetPeriod::Monthly => "month",
+        }
+    }
+}
+
+/// 单个预算的当前状态
+#[derive(Debug, Clone)]
+pub struct BudgetStatus {
+    pub period: BudgetPeriod,
+    pub spent: f64,
+    pub limit: f64,
+    pub level: BudgetLevel,
+    /// 周期标识（会话 id、日期、周或月份），换周期后重新告警
+    pub window: String,
+}
+
+impl BudgetStatus {
+    pub fn percent(&self) -> f64 {
+        if self.limit > 0.0 {
+            self.spent / self.limit * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// 是否设置了任何预算，未设置时不需要计算
+pub fn is_configured(config: &BudgetConfig) -> bool {
+    config.source == BudgetSource::Packycode
+        || [config.session, config.daily, config.weekly, config.monthly]
+            .iter()
+            .any(Option::is_some)
+}
+
+/// 一次渲染中共享的预算状态，第一次使用时计算，预算 segment 和整行的警告色共用
+#[derive(Debug, Clone)]
+pub struct SharedStatuses {
+    config: Rc<Config>,
+    usage: SharedEntries,
+    statuses: Rc<OnceCell<Vec<BudgetStatus>>>,
+}
+
+impl Default for SharedStatuses {
+    fn default() -> Self {
+        Self::new(Config::default(), SharedEntries::default())
+    }
+}
+
+impl SharedStatuses {
+    pub fn new(config: Config, usage: SharedEntries) -> Self {
+        Self {
+            config: Rc::new(config),
+            usage,
+            statuses: Rc::new(OnceCell::new()),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn get(&self, input: &InputData) -> &[BudgetStatus] {
+        self.statuses
+            .get_or_init(|| evaluate(input, &self.config, &self.usage))
+    }
+
+    /// 最严重的预算级别，没有预算时为 Normal
+    pub fn level(&self, input: &InputData) -> BudgetLevel {
+        self.get(input)
+            .iter()
+            .map(|status| status.level)
+            .max()
+            .unwrap_or(BudgetLevel::Normal)
+    }
+}
+
+/// 计算所有已配置预算的使用情况
+pub fn evaluate(input: &InputData, config: &Config, usage: &SharedEntries) -> Vec<BudgetStatus> {
+    let budget = &config.budget;
+    let engine = CostEngine::from_config(ModelRegistry::new(&config.models), &config.cost);
+    let quota = match budget.source {
+        BudgetSource::Packycode => quota::fetch(budget.quota_ttl_secs),
+        BudgetSource::Local => None,
+    };
+    let now = Utc::now();
+    let today = daily::current_date(now, &config.daily);
+    let mut statuses = Vec::new();
+
+    if let Some(limit) = budget.session {
+        let spent = input
+            .cost
+            .as_ref()
+            .and_then(|c| c.total_cost_usd)
+            .unwrap_or_else(|| {
+                input
+                    .transcript_path
+                    .as_deref()
+                    .map(transcript::session_entries)
+                    .map_or(0.0, |entries| engine.session_cost(&entries).total.total())
+            });
+        let window = input.session_id.clone().unwrap_or_default();
+        statuses.push(status(BudgetPeriod::Session, spent, limit, window, budget));
+    }
+
+    // PackyCode 模式下没有单独配置的日/月预算时使用账户额度
+    let daily_limit = budget.daily.or_else(|| quota.and_then(|q| q.daily_budget));
+    if let Some(limit) = daily_limit {
+        let spent = match quota.and_then(|q| q.daily_spent) {
+            Some(spent) => spent,
+            None => daily::today_spend(usage, &engine, &config.daily),
+        };
+        let window = daily::day_start(now, &config.daily).timestamp().to_string();
+        statuses.push(status(BudgetPeriod::Daily, spent, limit, window, budget));
+    }
+
+    if let Some(limit) = budget.weekly {
+        let spent = daily::spend_since(usage, &engine, daily::week_start(now, &config.daily));
+        let window = today.format("%G-W%V").to_string();
+        statuses.push(status(BudgetPeriod::Weekly, spent, limit, window, budget));
+    }
+
+    let monthly_limit = budget
+        .monthly
+        .or_else(|| quota.and_then(|q| q.monthly_budget));
+    if let Some(limit) = monthly_limit {
+        let spent = match quota.and_then(|q| q.monthly_spent) {
+            Some(spent) => spent,
+            None => daily::spend_since(usage, &engine, daily::month_start(now, &config.daily)),
+        };
+        let window = today.format("%Y-%m").to_string();
+        statuses.push(status(BudgetPeriod::Monthly, spent, limit, window, budget));
+    }
+
+    statuses
+}
+
+fn status(
+    period: BudgetPeriod,
+    spent: f64,
+    limit: f64,
+    window: String,
+    budget: &BudgetConfig,
+) -> BudgetStatus {
+    let mut status = BudgetStatus {
+        period,
+        spent,
+        limit,
+        level: BudgetLevel::Normal,
+        window,
+    };
+    let percent = status.percent();
+    if percent >= budget.critical_percent {
+        status.level = BudgetLevel::Critical;
+    } else if percent >= budget.warning_percent {
+        status.level = BudgetLevel::Warning;
+    }
+    status
+}
+
+/// 预算需要的最早用量记录时间，日/周/月预算都未设置时返回 None
+pub fn usage_since(config: &Config) -> Option<i64> {
+    let budget = &config.budget;
+    let now = Utc::now();
+    // PackyCode 额度可能缺少已用金额，此时回退到本地用量
+    let packycode = budget.source == BudgetSource::Packycode;
+    [
+        (budget.monthly.is_some() || packycode).then(|| daily::month_start(now, &config.daily)),
+        budget
+            .weekly
+            .is_some()
+            .then(|| daily::week_start(now, &config.daily)),
+        (budget.daily.is_some() || packycode).then(|| daily::day_start(now, &config.daily)),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|start| start.timestamp())
+    .min()
+}
+
+/// 某个周期已触发的最高级别
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Alert {
+    level: BudgetLevel,
+    /// 触发时的 Unix 时间（秒），记录过多时先删除最早的
+    fired_at: i64,
+}
+
+/// 预算进入更高级别时执行配置的命令，每个周期的每个级别只执行一次
+pub fn notify(statuses: &[BudgetStatus], budget: &BudgetConfig) {
+    let Some(command) = budget.command.as_deref().filter(|c| !c.is_empty()) else {
+        return;
+    };
+
+    let mut alerts: BTreeMap<String, Alert> = cache::load(ALERTS_FILE);
+    let fired = pending_alerts(&mut alerts, statuses, Utc::now().timestamp());
+    for status in &fired {
+        run_command(command, status);
+    }
+    if !fired.is_empty() {
+        cache::store(ALERTS_FILE, &alerts);
+    }
+}
+
+fn alert_key(status: &BudgetStatus) -> String {
+    format!("{}:{}", status.period.label(), status.window)
+}
+
+/// 返回需要告警的预算并记录到 alerts；记录过多时删除最早的，当前周期的记录不会被删除
+fn pending_alerts<'a>(
+    alerts: &mut BTreeMap<String, Alert>,
+    statuses: &'a [BudgetStatus],
+    now: i64,
+) -> Vec<&'a BudgetStatus> {
+    let live: Vec<String> = statuses.iter().map(alert_key).collect();
+    let mut fired = Vec::new();
+    for status in statuses {
+        if status.level == BudgetLevel::Normal {
+            continue;
+        }
+        let key = alert_key(status);
+        if alerts
+            .get(&key)
+            .is_some_and(|alert| alert.level >= status.level)
+        {
+            continue;
+        }
+
+        cache::evict_oldest(
+            alerts,
+            ALERTS_LIMIT,
+            |alert| alert.fired_at,
+            |key| live.iter().any(|live| live == key),
+        );
+        alerts.insert(
+            key,
+            Alert {
+                level: status.level,
+                fired_at: now,
+            },
+        );
+        fired.push(status);
+    }
+    fired
+}
+
+/// 在后台执行命令，预算信息通过环境变量传递，输出不会混入 statusline
+fn run_command(command: &str, status: &BudgetStatus) {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let _ = shell
+        .arg(command)
+        .env("CCLINE_BUDGET_PERIOD", status.period.label())
+        .env("CCLINE_BUDGET_LEVEL", status.level.as_str())
+        .env("CCLINE_BUDGET_SPENT", format!("{:.2}", status.spent))
+        .env("CCLINE_BUDGET_LIMIT", format!("{:.2}", status.limit))
+        .env("CCLINE_BUDGET_PERCENT", format!("{:.0}", status.percent()))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_configured, pending_alerts, Alert, BudgetLevel, BudgetPeriod, BudgetStatus, ALERTS_LIMIT,
+    };
+    use crate::config::{BudgetConfig, BudgetSource};
+    use std::collections::BTreeMap;
+
+    fn status(period: BudgetPeriod, window: &str, level: BudgetLevel) -> BudgetStatus {
+        BudgetStatus {
+            period,
+            spent: 0.0,
+            limit: 1.0,
+            level,
+            window: window.to_string(),
+        }
+    }
+
+    #[test]
+    fn levels_start_at_the_thresholds() {
+        let budget = BudgetConfig {
+            warning_percent: 80.0,
+            critical_percent: 100.0,
+            ..Default::default()
+        };
+        let level = |spent: f64, limit: f64| {
+            super::status(BudgetPeriod::Daily, spent, limit, String::new(), &budget).level
+        };
+        assert_eq!(level(0.0, 10.0), BudgetLevel::Normal);
+        assert_eq!(level(7.99, 10.0), BudgetLevel::Normal);
+        assert_eq!(level(8.0, 10.0), BudgetLevel::Warning);
+        assert_eq!(level(9.99, 10.0), BudgetLevel::Warning);
+        assert_eq!(level(10.0, 10.0), BudgetLevel::Critical);
+        assert_eq!(level(25.0, 10.0), BudgetLevel::Critical);
+        // 预算为 0 时不告警
+        assert_eq!(level(5.0, 0.0), BudgetLevel::Normal);
+    }
+
+    #[test]
+    fn budgets_are_configured_by_limits_or_packycode() {
+        let mut budget = BudgetConfig::default();
+        assert!(!is_configured(&budget));
+        budget.weekly = Some(50.0);
+        assert!(is_configured(&budget));
+        let packycode = BudgetConfig {
+            source: BudgetSource::Packycode,
+            ..Default::default()
+        };
+        assert!(is_configured(&packycode));
+    }
+
+    #[test]
+    fn full_alert_log_keeps_current_windows() {
+        let statuses = [
+            status(BudgetPeriod::Daily, "today", BudgetLevel::Warning),
+            status(BudgetPeriod::Monthly, "2026-10", BudgetLevel::Warning),
+        ];
+        let mut alerts = BTreeMap::new();
+        assert_eq!(pending_alerts(&mut alerts, &statuses[..1], 0).len(), 1);
+        // 当天的告警是最早的记录，之后填满旧周期的记录
+        for i in 0..ALERTS_LIMIT {
+            let alert = Alert {
+                level: BudgetLevel::Critical,
+                fired_at: 1 + i as i64,
+            };
+            alerts.insert(format!("day:old-{}", i), alert);
+        }
+
+        let fired = pending_alerts(&mut alerts, &statuses, 1000);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].period, BudgetPeriod::Monthly);
+        assert!(alerts.len() <= ALERTS_LIMIT);
+        assert!(alerts.contains_key("day:today"));
+        assert!(!alerts.contains_key("day:old-0"));
+
+        assert!(pending_alerts(&mut alerts, &statuses, 1001).is_empty());
+    }
+
+    #[test]
+    fn higher_level_fires_again() {
+        let mut alerts = BTreeMap::new();
+        let warning = [status(BudgetPeriod::Daily, "today", BudgetLevel::Warning)];
+        let critical = [status(BudgetPeriod::Daily, "today", BudgetLevel::Critical)];
+        assert_eq!(pending_alerts(&mut alerts, &warning, 0).len(), 1);
+        assert!(pending_alerts(&mut alerts, &warning, 1).is_empty());
+        assert_eq!(pending_alerts(&mut alerts, &critical, 2).len(), 1);
+        assert!(pending_alerts(&mut alerts, &warning, 3).is_empty());
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::ConfigLoader;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// 缓存目录：~/.claude/ccline/cache
+pub fn cache_dir() -> Option<PathBuf> {
+    ConfigLoader::ccline_dir().map(|dir| dir.join("cache"))
+}
+
+/// 读取 JSON 缓存文件，不存在或损坏时返回默认值
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    cache_dir()
+        .and_then(|dir| fs::read_to_string(dir.join(name)).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 写入 JSON 缓存文件，先写临时文件再重命名，避免并发渲染读到半个文件
+pub fn store<T: Serialize>(name: &str, value: &T) {
+    let Some(dir) = cache_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&dir).is_err() {
+        return;
+    }
+
+    let Ok(content) = serde_json::to_string(value) else {
+        return;
+    };
+    let tmp = dir.join(format!("{}.{}.tmp", name, std::process::id()));
+    if fs::write(&tmp, content).is_ok() && fs::rename(&tmp, dir.join(name)).is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+}
+
+/// 为新条目腾出位置：条目数达到 limit 时按 age 从旧到新删除，keep 为 true 的条目保留
+pub fn evict_oldest<V, A: Ord>(
+    map: &mut BTreeMap<String, V>,
+    limit: usize,
+    age: impl Fn(&V) -> A,
+    keep: impl Fn(&str) -> bool,
+) {
+    if map.len() < limit {
+        return;
+    }
+    let mut candidates: Vec<(A, String)> = map
+        .iter()
+        .filter(|(key, _)| !keep(key))
+        .map(|(key, value)| (age(value), key.clone()))
+        .collect();
+    candidates.sort();
+    for (_, key) in candidates {
+        if map.len() < limit {
+            break;
+        }
+        map.remove(&key);
+    }
+}
+
+/// 删除不再使用的缓存文件
+pub fn remove(name: &str) {
+    if let Some(dir) = cache_dir() {
+        let _ = fs::remove_file(dir.join(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::evict_oldest;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn evicts_oldest_first() {
+        let mut map: BTreeMap<String, u64> = [("a", 3), ("b", 1), ("c", 2), ("d", 0)]
+            .into_iter()
+            .map(|(key, age)| (key.to_string(), age))
+            .collect();
+        evict_oldest(&mut map, 3, |age| *age, |key| key == "d");
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "d"]);
+
+        evict_oldest(&mut map, 3, |age| *age, |_| false);
+        assert_eq!(map.len(), 2);
+    }
+}
@@ -0,0 +1,45 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Claude Code 的配置目录：设置了 CLAUDE_CONFIG_DIR 时只使用该目录，
+/// 否则为 ~/.claude 和新版本的 ~/.config/claude
+pub fn config_dirs() -> Vec<PathBuf> {
+    if let Some(dir) = env::var_os("CLAUDE_CONFIG_DIR").filter(|dir| !dir.is_empty()) {
+        return vec![PathBuf::from(dir)];
+    }
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    vec![home.join(".claude"), home.join(".config").join("claude")]
+}
+
+/// 用户级 settings.json：第一个已存在的文件，都不存在时为第一个配置目录下的 settings.json
+pub fn user_settings_path() -> Option<PathBuf> {
+    let candidates: Vec<PathBuf> = config_dirs()
+        .into_iter()
+        .map(|dir| dir.join("settings.json"))
+        .collect();
+    candidates
+        .iter()
+        .find(|path| path.exists())
+        .or(candidates.first())
+        .cloned()
+}
+
+/// 所有已存在的用户级 settings.json
+pub fn user_settings_files() -> Vec<PathBuf> {
+    config_dirs()
+        .into_iter()
+        .map(|dir| dir.join("settings.json"))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// 保存 transcript 的 projects 目录
+pub fn projects_dirs() -> Vec<PathBuf> {
+    config_dirs()
+        .into_iter()
+        .map(|dir| dir.join("projects"))
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
@@ -0,0 +1,117 @@
+use crate::config::DailyConfig;
+use crate::core::index::SharedEntries;
+use crate::core::pricing::{CostBreakdown, CostEngine};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, TimeZone, Utc};
+
+/// 当天的起点：在配置的时区中，最近一次经过 day_start_hour 的时刻
+pub fn day_start(now: DateTime<Utc>, config: &DailyConfig) -> DateTime<Utc> {
+    date_start(current_date(now, config), config).unwrap_or(now - Duration::hours(24))
+}
+
+/// 本周一的起点，与当天的起点使用相同的时区和 day_start_hour
+pub fn week_start(now: DateTime<Utc>, config: &DailyConfig) -> DateTime<Utc> {
+    let date = current_date(now, config);
+    let monday = date - Duration::days(date.weekday().num_days_from_monday().into());
+    date_start(monday, config).unwrap_or(now - Duration::days(7))
+}
+
+/// 本月 1 日的起点，与当天的起点使用相同的时区和 day_start_hour
+pub fn month_start(now: DateTime<Utc>, config: &DailyConfig) -> DateTime<Utc> {
+    let date = current_date(now, config);
+    date_start(date.with_day(1).unwrap_or(date), config).unwrap_or(now - Duration::days(31))
+}
+
+/// 按 day_start_hour 划分的当前日期，如凌晨 4 点开始新的一天时 3 点仍属于前一天
+pub fn current_date(now: DateTime<Utc>, config: &DailyConfig) -> NaiveDate {
+    let hour = Duration::hours(config.day_start_hour.min(23).into());
+    // 未设置或无法解析时使用本地时区
+    let local = match config.timezone.as_deref().and_then(parse_offset) {
+        Some(offset) => now.with_timezone(&offset).naive_local(),
+        None => now.with_timezone(&Local).naive_local(),
+    };
+    (local - hour).date()
+}
+
+fn date_start(date: NaiveDate, config: &DailyConfig) -> Option<DateTime<Utc>> {
+    let start = date.and_hms_opt(config.day_start_hour.min(23), 0, 0)?;
+    match config.timezone.as_deref().and_then(parse_offset) {
+        Some(offset) => to_utc(&offset, start),
+        None => to_utc(&Local, start),
+    }
+}
+
+fn to_utc<Tz: TimeZone>(tz: &Tz, local: chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+    tz.from_local_datetime(&local)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+/// 解析 "utc" 或 "+08:00" / "-0530" 形式的固定时区
+fn parse_offset(tz: &str) -> Option<FixedOffset> {
+    if tz.eq_ignore_ascii_case("utc") || tz == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+
+    let (sign, rest) = match tz.as_bytes().first()? {
+        b'+' => (1, &tz[1..]),
+        b'-' => (-1, &tz[1..]),
+        _ => return None,
+    };
+    let digits: String = rest.chars().filter(|c| *c != ':').collect();
+    // 只接受 ASCII 数字，下面按字节切分时不会落在多字节字符中间
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (hours, minutes) = match digits.len() {
+        2 => (digits.parse::<i32>().ok()?, 0),
+        4 => (
+            digits[..2].parse::<i32>().ok()?,
+            digits[2..].parse::<i32>().ok()?,
+        ),
+        _ => return None,
+    };
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// 当天所有会话的费用
+pub fn today_spend(usage: &SharedEntries, engine: &CostEngine, config: &DailyConfig) -> f64 {
+    spend_since(usage, engine, day_start(Utc::now(), config))
+}
+
+/// 所有会话在 start 之后的费用，按每条用量记录的时间归属
+pub fn spend_since(usage: &SharedEntries, engine: &CostEngine, start: DateTime<Utc>) -> f64 {
+    usage
+        .since(start.timestamp())
+        .iter()
+        .filter_map(|entry| {
+            let pricing = engine.pricing_for(&entry.model)?;
+            Some(CostBreakdown::from_usage(&entry.usage, &pricing).total() * engine.multiplier())
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_offset;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn parses_fixed_offsets() {
+        assert_eq!(
+            parse_offset("+05:30"),
+            FixedOffset::east_opt(5 * 3600 + 30 * 60)
+        );
+        assert_eq!(parse_offset("-0800"), FixedOffset::west_opt(8 * 3600));
+        assert_eq!(parse_offset("+08"), FixedOffset::east_opt(8 * 3600));
+        assert_eq!(parse_offset("UTC"), FixedOffset::east_opt(0));
+    }
+
+    #[test]
+    fn rejects_invalid_offsets() {
+        assert_eq!(parse_offset("+1é1"), None);
+        assert_eq!(parse_offset("+０８:００"), None);
+        assert_eq!(parse_offset("+8:0"), None);
+        assert_eq!(parse_offset("Asia/Shanghai"), None);
+        assert_eq!(parse_offset(""), None);
+    }
+}
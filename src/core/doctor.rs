@@ -0,0 +1,411 @@
+use crate::config::{BudgetSource, Config, ConfigLoader, InputData};
+use crate::core::cache;
+use crate::core::install::{self, SettingsScope};
+use crate::core::output::terminal_supports_hyperlinks;
+use crate::core::quota;
+use crate::core::StatusLineGenerator;
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+/// Claude Code 大约每 300ms 刷新一次 statusline，单个 segment 超过这些耗时会拖慢刷新
+const SLOW_SEGMENT: Duration = Duration::from_millis(50);
+const TOO_SLOW_SEGMENT: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+
+    fn ansi_color(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "\x1b[32m",
+            CheckStatus::Warn => "\x1b[33m",
+            CheckStatus::Fail => "\x1b[31m",
+        }
+    }
+}
+
+/// 一项诊断结果
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// 依次执行所有诊断，用 input 测量各个 segment 的渲染耗时
+pub fn run(config_path: Option<&str>, input: &InputData) -> Vec<Check> {
+    let (config_check, config) = check_config(config_path);
+    let mut checks = vec![
+        config_check,
+        check_git(&config),
+        check_icons(),
+        check_colors(),
+        check_settings(),
+        check_packycode(&config),
+        check_cache_dir(),
+    ];
+    checks.extend(check_segments(config, input));
+    checks
+}
+
+/// 每行一项：状态、名称和说明，输出到终端时着色
+pub fn format(checks: &[Check], color: bool) -> String {
+    let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+    checks
+        .iter()
+        .map(|check| {
+            let label = if color {
+                format!(
+                    "{}{}\x1b[0m",
+                    check.status.ansi_color(),
+                    check.status.label()
+                )
+            } else {
+                check.status.label().to_string()
+            };
+            format!(
+                "[{}] {:<width$}  {}",
+                label,
+                check.name,
+                check.message,
+                width = width
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn check_config(config_path: Option<&str>) -> (Check, Config) {
+    let (path, explicit) = match config_path {
+        Some(path) => (Some(PathBuf::from(path)), true),
+        None => (ConfigLoader::config_path(), false),
+    };
+    let Some(path) = path else {
+        return (
+            Check::new(
+                "config",
+                CheckStatus::Warn,
+                "cannot determine the home directory, using defaults",
+            ),
+            Config::default(),
+        );
+    };
+
+    if !path.exists() {
+        let check = if explicit {
+            Check::new(
+                "config",
+                CheckStatus::Fail,
+                format!("{} does not exist", path.display()),
+            )
+        } else {
+            Check::new(
+                "config",
+                CheckStatus::Pass,
+                format!("no file at {}, using built-in defaults", path.display()),
+            )
+        };
+        return (check, Config::default());
+    }
+
+    // 正常渲染时解析失败会静默回退到默认配置，这里把错误显示出来
+    match ConfigLoader::load_from_path(&path) {
+        Ok(config) => (
+            Check::new(
+                "config",
+                CheckStatus::Pass,
+                format!("loaded {}", path.display()),
+            ),
+            config,
+        ),
+        Err(e) => (
+            Check::new(
+                "config",
+                CheckStatus::Fail,
+                format!(
+                    "{} is invalid, defaults are used instead: {}",
+                    path.display(),
+                    e
+                ),
+            ),
+            Config::default(),
+        ),
+    }
+}
+
+fn check_git(config: &Config) -> Check {
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => Check::new(
+            "git",
+            CheckStatus::Pass,
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => {
+            let status = if config.segments.git {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            Check::new(
+                "git",
+                status,
+                "git not found in PATH, the git segment stays empty",
+            )
+        }
+    }
+}
+
+fn check_icons() -> Check {
+    // 无法检测终端字体，只能排除明显不支持的终端
+    if env::var("TERM").is_ok_and(|term| term == "linux") {
+        return Check::new(
+            "icons",
+            CheckStatus::Warn,
+            "the Linux console cannot show Nerd Font icons",
+        );
+    }
+    Check::new(
+        "icons",
+        CheckStatus::Pass,
+        "git and project icons need a Nerd Font (\u{e0a0} \u{e7a8}); install one if they show as boxes",
+    )
+}
+
+fn check_colors() -> Check {
+    let term = env::var("TERM").unwrap_or_default();
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    let hyperlinks = if terminal_supports_hyperlinks() {
+        ", hyperlinks supported"
+    } else {
+        ", hyperlinks off in auto mode"
+    };
+
+    if env::var_os("NO_COLOR").is_some() {
+        return Check::new(
+            "colors",
+            CheckStatus::Warn,
+            "NO_COLOR is set, colors may be stripped",
+        );
+    }
+    if term == "dumb" {
+        return Check::new(
+            "colors",
+            CheckStatus::Warn,
+            "TERM=dumb, colors are not supported",
+        );
+    }
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return Check::new(
+            "colors",
+            CheckStatus::Pass,
+            format!("24-bit color{}", hyperlinks),
+        );
+    }
+    if term.contains("256color") {
+        return Check::new(
+            "colors",
+            CheckStatus::Pass,
+            format!("256 colors (TERM={}){}", term, hyperlinks),
+        );
+    }
+    Check::new(
+        "colors",
+        CheckStatus::Warn,
+        format!(
+            "TERM={} may only support 16 colors, 256-color styles are approximated{}",
+            if term.is_empty() { "unset" } else { &term },
+            hyperlinks
+        ),
+    )
+}
+
+fn check_settings() -> Check {
+    // 按 Claude Code 的优先级查找，第一个配置了 statusLine 的文件生效
+    let scopes = [
+        ("local", SettingsScope::Local),
+        ("project", SettingsScope::Project),
+        ("user", SettingsScope::User),
+    ];
+    for (label, scope) in scopes {
+        let Some(path) = install::settings_path(scope) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let settings: Value = match serde_json::from_str(&content) {
+            Ok(settings) => settings,
+            Err(e) => {
+                return Check::new(
+                    "settings",
+                    CheckStatus::Fail,
+                    format!("{} is not valid JSON: {}", path.display(), e),
+                )
+            }
+        };
+        let Some(command) = settings
+            .get("statusLine")
+            .and_then(|s| s.get("command"))
+            .and_then(Value::as_str)
+        else {
+            continue;
+        };
+
+        // 命令可能带参数，只检查第一个词对应的文件
+        let program = install::command_program(command);
+        let program = program.as_str();
+        let expanded = match program.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map_or(PathBuf::from(program), |h| h.join(rest)),
+            None => PathBuf::from(program),
+        };
+        let found = expanded.exists() || which(program);
+        let status = if found {
+            CheckStatus::Pass
+        } else {
+            CheckStatus::Fail
+        };
+        let note = if found { "" } else { " (not found)" };
+        return Check::new(
+            "settings",
+            status,
+            format!(
+                "{} statusLine runs {}{} ({})",
+                label,
+                command,
+                note,
+                path.display()
+            ),
+        );
+    }
+    Check::new(
+        "settings",
+        CheckStatus::Warn,
+        "no statusLine in Claude Code settings.json, run `ccline install`",
+    )
+}
+
+/// 命令名是否能在 PATH 中找到
+fn which(program: &str) -> bool {
+    if program.contains(['/', '\\']) {
+        return false;
+    }
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|dir| {
+            dir.join(program).is_file() || dir.join(format!("{}.exe", program)).is_file()
+        })
+    })
+}
+
+fn check_packycode(config: &Config) -> Check {
+    let required = config.budget.source == BudgetSource::Packycode;
+    let Some(credentials) = quota::credentials() else {
+        let status = if required {
+            CheckStatus::Fail
+        } else {
+            CheckStatus::Pass
+        };
+        return Check::new(
+            "packycode",
+            status,
+            "no ANTHROPIC_AUTH_TOKEN in the environment or settings.json, quota not used",
+        );
+    };
+
+    match quota::request_with(&credentials) {
+        Ok(quota) => {
+            let spent = quota
+                .daily_spent
+                .map(|spent| format!(", today ${:.2}", spent))
+                .unwrap_or_default();
+            Check::new(
+                "packycode",
+                CheckStatus::Pass,
+                format!("{} reachable{}", credentials.info_url, spent),
+            )
+        }
+        Err(e) => Check::new(
+            "packycode",
+            if required {
+                CheckStatus::Fail
+            } else {
+                CheckStatus::Warn
+            },
+            format!("{} failed: {}", credentials.info_url, e),
+        ),
+    }
+}
+
+fn check_cache_dir() -> Check {
+    let Some(dir) = cache::cache_dir() else {
+        return Check::new(
+            "cache",
+            CheckStatus::Fail,
+            "cannot determine the home directory",
+        );
+    };
+    let probe = dir.join(format!(".doctor.{}", std::process::id()));
+    let result = fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&probe, b"ok"))
+        .and_then(|_| fs::remove_file(&probe));
+    match result {
+        Ok(()) => Check::new(
+            "cache",
+            CheckStatus::Pass,
+            format!("{} is writable", dir.display()),
+        ),
+        Err(e) => Check::new(
+            "cache",
+            CheckStatus::Fail,
+            format!("{} is not writable: {}", dir.display(), e),
+        ),
+    }
+}
+
+fn check_segments(config: Config, input: &InputData) -> Vec<Check> {
+    let generator = StatusLineGenerator::new(config).with_alerts(false);
+    generator
+        .timings(input)
+        .into_iter()
+        .map(|(name, elapsed, has_output)| {
+            let status = if elapsed >= TOO_SLOW_SEGMENT {
+                CheckStatus::Fail
+            } else if elapsed >= SLOW_SEGMENT {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            let output = if has_output { "" } else { ", no output" };
+            Check::new(
+                format!("segment {}", name),
+                status,
+                format!("{:.1}ms{}", elapsed.as_secs_f64() * 1000.0, output),
+            )
+        })
+        .collect()
+}
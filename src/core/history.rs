@@ -0,0 +1,327 @@
+use crate::config::{Config, ConfigLoader, InputData};
+use crate::core::cache;
+use crate::core::index::FileIndex;
+use crate::core::models::ModelRegistry;
+use crate::core::pricing::{CostBreakdown, CostEngine};
+use crate::core::transcript::TokenUsage;
+use chrono::{DateTime, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 各会话上一次写入的指纹，用于跳过没有变化的渲染；多个会话同时运行时互不影响
+const LAST_WRITE_FILE: &str = "history_last.json";
+const LAST_WRITE_LIMIT: usize = 64;
+/// 各会话 transcript 的读取进度，只在需要写入时读取
+const PROGRESS_FILE: &str = "history_progress.json";
+const PROGRESS_LIMIT: usize = 8;
+/// 历史文件超过该大小时压缩为每个会话一条记录
+const COMPACT_BYTES: u64 = 4 * 1024 * 1024;
+/// 压缩锁超过该时间仍存在时视为进程已崩溃
+const COMPACT_LOCK_SECS: u64 = 60;
+
+/// 单个模型在会话中的用量和费用
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelUsage {
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+}
+
+/// 一个会话的最新快照，history.jsonl 中同一会话出现多次时以最后一条为准
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionRecord {
+    pub session_id: String,
+    /// 项目目录，缺失时为当前工作目录
+    pub project: String,
+    /// 最近使用的模型显示名
+    pub model: String,
+    /// transcript 中第一条和最后一条带用量消息的时间戳（RFC 3339）
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    /// 写入快照的 Unix 时间（秒）
+    pub recorded_at: u64,
+    /// Claude Code 报告的费用，未报告时为根据 transcript 计算的费用
+    pub cost_usd: f64,
+    pub duration_ms: u64,
+    pub api_duration_ms: u64,
+    pub lines_added: u32,
+    pub lines_removed: u32,
+    pub usage: TokenUsage,
+    /// 按模型 id 拆分的用量
+    pub models: BTreeMap<String, ModelUsage>,
+}
+
+/// 会话上一次写入时的指纹，与当前相同时跳过写入
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Fingerprint {
+    transcript_len: u64,
+    cost_usd: Option<f64>,
+    lines: (u32, u32),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LastWrite {
+    #[serde(flatten)]
+    fingerprint: Fingerprint,
+    /// 写入的 Unix 时间（秒），会话过多时先删除最久没有写入的
+    #[serde(default)]
+    written_at: u64,
+}
+
+/// 会话 transcript 的增量读取进度，写入快照时只解析新追加的行
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SessionProgress {
+    transcript: String,
+    index: FileIndex,
+    /// 最近使用的 Unix 时间（秒），超过 PROGRESS_LIMIT 时淘汰最久未用的会话
+    used_at: u64,
+}
+
+/// 历史文件路径，默认为 ~/.claude/ccline/history.jsonl
+pub fn history_path(config: &Config) -> Option<PathBuf> {
+    match &config.history.path {
+        Some(path) => Some(PathBuf::from(path)),
+        None => ConfigLoader::ccline_dir().map(|dir| dir.join("history.jsonl")),
+    }
+}
+
+/// 记录当前会话的快照；会话自上次写入后没有变化时只做一次 stat，不解析 transcript
+pub fn record(input: &InputData, config: &Config) {
+    if !config.history.enabled {
+        return;
+    }
+    let Some(session_id) = input.session_id.as_deref().filter(|id| !id.is_empty()) else {
+        return;
+    };
+    let Some(path) = history_path(config) else {
+        return;
+    };
+
+    let cost = input.cost.as_ref();
+    let transcript_len = input
+        .transcript_path
+        .as_deref()
+        .and_then(|p| fs::metadata(p).ok())
+        .map_or(0, |meta| meta.len());
+    let fingerprint = Fingerprint {
+        transcript_len,
+        cost_usd: cost.and_then(|c| c.total_cost_usd),
+        lines: (
+            cost.and_then(|c| c.total_lines_added).unwrap_or(0),
+            cost.and_then(|c| c.total_lines_removed).unwrap_or(0),
+        ),
+    };
+    let mut last: BTreeMap<String, LastWrite> = cache::load(LAST_WRITE_FILE);
+    if last
+        .get(session_id)
+        .is_some_and(|last| last.fingerprint == fingerprint)
+    {
+        return;
+    }
+
+    let record = snapshot(input, config, session_id, transcript_len);
+    if append(&path, &record).is_ok() {
+        cache::evict_oldest(
+            &mut last,
+            LAST_WRITE_LIMIT,
+            |last| last.written_at,
+            |_| false,
+        );
+        last.insert(
+            session_id.to_string(),
+            LastWrite {
+                fingerprint,
+                written_at: now_secs(),
+            },
+        );
+        cache::store(LAST_WRITE_FILE, &last);
+    }
+}
+
+/// 会话 transcript 的全部用量记录；从上次的进度继续读取，不重新解析整个文件
+fn session_index(input: &InputData, session_id: &str, transcript_len: u64) -> FileIndex {
+    let Some(transcript) = input.transcript_path.as_deref() else {
+        return FileIndex::default();
+    };
+
+    let mut sessions: BTreeMap<String, SessionProgress> = cache::load(PROGRESS_FILE);
+    let mut progress = sessions.remove(session_id).unwrap_or_default();
+    // transcript 换了文件时从头读取
+    if progress.transcript != transcript {
+        progress = SessionProgress {
+            transcript: transcript.to_string(),
+            ..SessionProgress::default()
+        };
+    }
+    progress
+        .index
+        .update(Path::new(transcript), transcript_len, i64::MIN);
+    progress.used_at = now_secs();
+
+    cache::evict_oldest(
+        &mut sessions,
+        PROGRESS_LIMIT,
+        |progress| progress.used_at,
+        |_| false,
+    );
+    sessions.insert(session_id.to_string(), progress);
+    cache::store(PROGRESS_FILE, &sessions);
+    sessions.remove(session_id).unwrap_or_default().index
+}
+
+fn snapshot(
+    input: &InputData,
+    config: &Config,
+    session_id: &str,
+    transcript_len: u64,
+) -> SessionRecord {
+    let engine = CostEngine::from_config(ModelRegistry::new(&config.models), &config.cost);
+    let index = session_index(input, session_id, transcript_len);
+
+    let mut usage = TokenUsage::default();
+    let mut models: BTreeMap<String, ModelUsage> = BTreeMap::new();
+    let mut computed = 0.0;
+    let mut span: Option<(i64, i64)> = None;
+    for entry in index.entries() {
+        usage.add(&entry.usage);
+        let cost = engine.pricing_for(&entry.model).map_or(0.0, |pricing| {
+            CostBreakdown::from_usage(&entry.usage, &pricing).total() * engine.multiplier()
+        });
+        computed += cost;
+        let model = models.entry(entry.model.clone()).or_default();
+        model.usage.add(&entry.usage);
+        model.cost_usd += cost;
+        span = Some(span.map_or((entry.time, entry.time), |(first, last)| {
+            (first.min(entry.time), last.max(entry.time))
+        }));
+    }
+    let rfc3339 = |time: i64| {
+        DateTime::from_timestamp(time, 0).map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+    };
+
+    let cost = input.cost.as_ref();
+    SessionRecord {
+        session_id: session_id.to_string(),
+        project: input
+            .workspace
+            .project_dir
+            .clone()
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or_else(|| input.current_dir().to_string()),
+        model: input.model.display_name.clone(),
+        started_at: span.and_then(|(first, _)| rfc3339(first)),
+        ended_at: span.and_then(|(_, last)| rfc3339(last)),
+        recorded_at: now_secs(),
+        cost_usd: cost.and_then(|c| c.total_cost_usd).unwrap_or(computed),
+        duration_ms: cost.and_then(|c| c.total_duration_ms).unwrap_or(0),
+        api_duration_ms: cost.and_then(|c| c.total_api_duration_ms).unwrap_or(0),
+        lines_added: cost.and_then(|c| c.total_lines_added).unwrap_or(0),
+        lines_removed: cost.and_then(|c| c.total_lines_removed).unwrap_or(0),
+        usage,
+        models,
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// 追加一行快照，文件过大时压缩
+fn append(path: &Path, record: &SessionRecord) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    // 单次 write 追加整行，并发渲染时不会交错
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+
+    if file.metadata()?.len() > COMPACT_BYTES {
+        compact(path)?;
+    }
+    Ok(())
+}
+
+/// 重写历史文件，每个会话只保留最新的快照；同一时间只有一个进程压缩
+fn compact(path: &Path) -> std::io::Result<()> {
+    let lock = path.with_extension("lock");
+    match OpenOptions::new().write(true).create_new(true).open(&lock) {
+        Ok(_) => {}
+        // 其他进程正在压缩；崩溃后残留的锁在超时后清除
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+            let stale = fs::metadata(&lock)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age.as_secs() > COMPACT_LOCK_SECS);
+            if stale {
+                let _ = fs::remove_file(&lock);
+            }
+            return Ok(());
+        }
+        Err(e) => return Err(e),
+    }
+
+    let result = rewrite(path);
+    let _ = fs::remove_file(&lock);
+    result
+}
+
+fn rewrite(path: &Path) -> std::io::Result<()> {
+    let len = fs::metadata(path)?.len();
+    let records = read_records(path);
+    let mut content = String::new();
+    for record in &records {
+        content.push_str(&serde_json::to_string(record)?);
+        content.push('\n');
+    }
+
+    // 压缩期间其他会话追加的行接在后面，不会丢失；重复的快照读取时以最后一条为准
+    let mut appended = Vec::new();
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(len))?;
+    file.read_to_end(&mut appended)?;
+    let complete = appended
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |end| end + 1);
+    content.push_str(&String::from_utf8_lossy(&appended[..complete]));
+
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// 读取所有会话的最新快照，按会话第一次出现的顺序排列，跳过损坏的行
+pub fn read_records<P: AsRef<Path>>(path: P) -> Vec<SessionRecord> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+
+    let mut records: Vec<SessionRecord> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let Ok(record) = serde_json::from_str::<SessionRecord>(&line) else {
+            continue;
+        };
+        match seen.get(&record.session_id) {
+            Some(&index) => records[index] = record,
+            None => {
+                seen.insert(record.session_id.clone(), records.len());
+                records.push(record);
+            }
+        }
+    }
+    records
+}
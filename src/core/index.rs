@@ -0,0 +1,200 @@
+use crate::core::cache;
+use crate::core::transcript::{self, TokenUsage};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::UNIX_EPOCH;
+
+const INDEX_FILE: &str = "usage_index.json";
+/// 旧版本只给当天费用使用的索引，已由 INDEX_FILE 取代
+const LEGACY_INDEX_FILE: &str = "daily_index.json";
+/// 索引至少保留最近这么长时间的记录；当天费用、5 小时计费块和预算共用同一份索引，
+/// 设置了周/月预算时保留到周/月的起点
+const RETAIN_SECS: i64 = 30 * 3600;
+
+/// 索引中的一条用量记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedEntry {
+    /// Unix 时间（秒）
+    pub time: i64,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+/// 单个 transcript 的增量读取进度，history 也用它按会话增量统计用量
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FileIndex {
+    /// 已读取到的字节偏移，下次从这里继续
+    offset: u64,
+    /// 按 message.id:requestId 去重的用量记录
+    entries: BTreeMap<String, IndexedEntry>,
+}
+
+/// 所有 transcript 最近用量的增量索引
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageIndex {
+    /// 索引包含该时间之后的全部记录
+    floor: i64,
+    files: BTreeMap<String, FileIndex>,
+}
+
+/// 所有 transcript 中 since 之后的用量记录，按时间排序；transcript 只读取上次之后追加的部分
+pub fn entries_since(since: i64) -> Vec<IndexedEntry> {
+    let floor = since.min(Utc::now().timestamp() - RETAIN_SECS);
+
+    let mut index: UsageIndex = cache::load(INDEX_FILE);
+    // 需要比索引更早的记录时重新建立索引
+    let mut changed = index.floor > since;
+    if changed {
+        index = UsageIndex::default();
+    }
+    index.floor = floor;
+
+    let mut files = BTreeMap::new();
+    for path in transcript::transcript_files() {
+        let Ok(meta) = fs::metadata(&path) else {
+            continue;
+        };
+        // 窗口内没有修改过的文件不可能包含窗口内的记录
+        let modified = meta
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs() as i64);
+        if modified < floor {
+            continue;
+        }
+
+        let key = path.to_string_lossy().to_string();
+        let mut file_index = index.files.remove(&key).unwrap_or_default();
+        changed |= file_index.update(&path, meta.len(), floor);
+        let before = file_index.entries.len();
+        file_index.entries.retain(|_, entry| entry.time >= floor);
+        changed |= file_index.entries.len() != before;
+        files.insert(key, file_index);
+    }
+    // 不再出现的文件（窗口内未修改或已删除）不写回索引
+    changed |= !index.files.is_empty();
+    index.files = files;
+
+    if changed {
+        cache::store(INDEX_FILE, &index);
+        cache::remove(LEGACY_INDEX_FILE);
+    }
+
+    // 同一条消息可能出现在多个 transcript 中（如恢复的会话），只计一次
+    let mut seen = BTreeMap::new();
+    for file_index in index.files.values() {
+        for (key, entry) in &file_index.entries {
+            if entry.time >= since {
+                seen.insert(key.clone(), entry.clone());
+            }
+        }
+    }
+    let mut entries: Vec<IndexedEntry> = seen.into_values().collect();
+    entries.sort_by_key(|entry| entry.time);
+    entries
+}
+
+/// 一次渲染中共享的用量记录，第一次使用时才读取索引，多个 segment 只读取一次
+#[derive(Debug, Clone)]
+pub struct SharedEntries {
+    since: i64,
+    entries: Rc<OnceCell<Vec<IndexedEntry>>>,
+}
+
+impl Default for SharedEntries {
+    fn default() -> Self {
+        Self::new(Utc::now().timestamp() - RETAIN_SECS)
+    }
+}
+
+impl SharedEntries {
+    /// since 为所有使用者需要的最早时间
+    pub fn new(since: i64) -> Self {
+        Self {
+            since,
+            entries: Rc::new(OnceCell::new()),
+        }
+    }
+
+    /// since 之后的记录，早于创建时 since 的部分不包含在内
+    pub fn since(&self, since: i64) -> &[IndexedEntry] {
+        let entries = self.entries.get_or_init(|| entries_since(self.since));
+        let start = entries.partition_point(|entry| entry.time < since);
+        &entries[start..]
+    }
+}
+
+impl FileIndex {
+    /// 读取 len 字节的文件中上次之后追加的行，早于 floor 的记录不保留；返回是否有变化
+    pub fn update(&mut self, path: &Path, len: u64, floor: i64) -> bool {
+        // 文件被截断或重写时从头读取
+        let reset = len < self.offset;
+        if reset {
+            *self = FileIndex::default();
+        }
+        let read = len > self.offset && read_new_lines(path, self, floor);
+        reset || read
+    }
+
+    /// 按 message.id:requestId 去重后的记录，未按时间排序
+    pub fn entries(&self) -> impl Iterator<Item = &IndexedEntry> {
+        self.entries.values()
+    }
+}
+
+/// 从上次的偏移读取新追加的完整行，返回是否有进度变化
+fn read_new_lines(path: &Path, file_index: &mut FileIndex, floor: i64) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    if file.seek(SeekFrom::Start(file_index.offset)).is_err() {
+        return false;
+    }
+    let mut buffer = Vec::new();
+    if file.read_to_end(&mut buffer).is_err() {
+        return false;
+    }
+
+    // 最后一行可能还没写完，留到下次读取
+    let Some(end) = buffer.iter().rposition(|b| *b == b'\n') else {
+        return false;
+    };
+    let content = String::from_utf8_lossy(&buffer[..end]);
+    for (number, line) in content.lines().enumerate() {
+        let Some(entry) = transcript::parse_line(line) else {
+            continue;
+        };
+        let Some(time) = entry
+            .timestamp
+            .as_deref()
+            .and_then(|ts| DateTime::parse_from_rfc3339(ts).ok())
+            .map(|ts| ts.timestamp())
+        else {
+            continue;
+        };
+        if time < floor {
+            continue;
+        }
+
+        let key = entry
+            .key
+            .unwrap_or_else(|| format!("{}#{}", file_index.offset, number));
+        file_index.entries.insert(
+            key,
+            IndexedEntry {
+                time,
+                model: entry.model,
+                usage: entry.usage,
+            },
+        );
+    }
+    file_index.offset += end as u64 + 1;
+    true
+}
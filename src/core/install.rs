@@ -0,0 +1,282 @@
+use crate::core::claude;
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Claude Code settings.json 的作用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SettingsScope {
+    /// ~/.claude/settings.json（或 CLAUDE_CONFIG_DIR、~/.config/claude 下已有的 settings.json）
+    User,
+    /// 当前项目的 .claude/settings.json（提交到仓库）
+    Project,
+    /// 当前项目的 .claude/settings.local.json（不提交）
+    Local,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstallOptions {
+    pub scope: SettingsScope,
+    /// 只打印修改后的内容，不写入文件
+    pub dry_run: bool,
+    /// statusLine 使用的命令，默认为当前可执行文件
+    pub command: Option<String>,
+    /// 卸载时即使 statusLine 不是 ccline 也删除
+    pub force: bool,
+}
+
+pub fn settings_path(scope: SettingsScope) -> Option<PathBuf> {
+    match scope {
+        SettingsScope::User => claude::user_settings_path(),
+        SettingsScope::Project => Some(PathBuf::from(".claude").join("settings.json")),
+        SettingsScope::Local => Some(PathBuf::from(".claude").join("settings.local.json")),
+    }
+}
+
+/// 添加或更新 statusLine，返回给用户的说明
+pub fn install(options: &InstallOptions) -> Result<String, Box<dyn Error>> {
+    let path = settings_path(options.scope).ok_or("cannot determine the home directory")?;
+    let command = match &options.command {
+        Some(command) => command.clone(),
+        None => shell_quote(&env::current_exe()?.to_string_lossy()),
+    };
+
+    let SettingsFile {
+        original,
+        mut settings,
+    } = read_settings(&path)?;
+    let status_line = json!({
+        "type": "command",
+        "command": command,
+        "padding": 0,
+    });
+    if settings.get("statusLine") == Some(&status_line) {
+        return Ok(format!("{} already uses {}", path.display(), command));
+    }
+    let previous = settings.insert("statusLine".to_string(), status_line);
+
+    let mut message = format!("statusLine in {} now runs {}", path.display(), command);
+    if let Some(previous) = previous {
+        message.push_str(&format!("\nreplaced previous statusLine: {}", previous));
+    }
+    write_settings(
+        &path,
+        original.as_deref(),
+        &settings,
+        options.dry_run,
+        message,
+    )
+}
+
+/// 删除 ccline 的 statusLine，其他配置保持不变
+pub fn uninstall(options: &InstallOptions) -> Result<String, Box<dyn Error>> {
+    let path = settings_path(options.scope).ok_or("cannot determine the home directory")?;
+    let SettingsFile {
+        original,
+        mut settings,
+    } = read_settings(&path)?;
+
+    let Some(status_line) = settings.get("statusLine") else {
+        return Ok(format!("{} has no statusLine", path.display()));
+    };
+    if !options.force && !is_ccline(status_line) {
+        return Err(format!(
+            "statusLine in {} is not ccline ({}); use --force to remove it anyway",
+            path.display(),
+            status_line
+        )
+        .into());
+    }
+    settings.shift_remove("statusLine");
+
+    let message = format!("removed statusLine from {}", path.display());
+    write_settings(
+        &path,
+        original.as_deref(),
+        &settings,
+        options.dry_run,
+        message,
+    )
+}
+
+fn is_ccline(status_line: &Value) -> bool {
+    let command = status_line
+        .get("command")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let current = env::current_exe()
+        .map(|exe| exe.to_string_lossy().to_string())
+        .unwrap_or_default();
+    command_program(command) == current
+        || command.contains("ccline")
+        || command.contains("ccometixline")
+}
+
+/// Claude Code 通过 shell 执行 statusLine 命令，路径中有空格等字符时需要加引号
+pub fn shell_quote(path: &str) -> String {
+    let safe = |c: char| {
+        c.is_ascii_alphanumeric() || "/._-+:@%,=".contains(c) || (cfg!(windows) && c == '\\')
+    };
+    if !path.is_empty() && path.chars().all(safe) {
+        return path.to_string();
+    }
+    if cfg!(windows) {
+        format!("\"{}\"", path)
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+/// 命令的第一个词（可执行文件），去掉引号和转义
+pub fn command_program(command: &str) -> String {
+    let mut program = String::new();
+    let mut quote = None;
+    let mut chars = command.trim_start().chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, c) if c.is_whitespace() => break,
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            // Windows 路径中的反斜杠是分隔符，不是转义
+            (None, '\\') if !cfg!(windows) => program.extend(chars.next()),
+            _ => program.push(c),
+        }
+    }
+    program
+}
+
+/// 读取到的 settings.json，original 为原始文本（文件不存在时为 None）
+struct SettingsFile {
+    original: Option<String>,
+    settings: Map<String, Value>,
+}
+
+/// 读取 settings.json，文件不存在时视为空对象；无法解析时报错，避免覆盖用户的配置
+fn read_settings(path: &Path) -> Result<SettingsFile, Box<dyn Error>> {
+    if !path.exists() {
+        return Ok(SettingsFile {
+            original: None,
+            settings: Map::new(),
+        });
+    }
+    let content = fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok(SettingsFile {
+            original: Some(content),
+            settings: Map::new(),
+        });
+    }
+    match serde_json::from_str(&content) {
+        Ok(Value::Object(settings)) => Ok(SettingsFile {
+            original: Some(content),
+            settings,
+        }),
+        Ok(_) => Err(format!("{} is not a JSON object", path.display()).into()),
+        Err(e) => Err(format!("cannot parse {}: {}", path.display(), e).into()),
+    }
+}
+
+/// 按原文件的缩进和结尾换行写回；第一次修改前备份原文件，之后不再覆盖备份
+fn write_settings(
+    path: &Path,
+    original: Option<&str>,
+    settings: &Map<String, Value>,
+    dry_run: bool,
+    message: String,
+) -> Result<String, Box<dyn Error>> {
+    let indent = original.map_or("  ".to_string(), detect_indent);
+    let mut content = to_string_with_indent(settings, &indent)?;
+    if original.is_none_or(|o| o.ends_with('\n')) {
+        content.push('\n');
+    }
+
+    if dry_run {
+        return Ok(format!(
+            "{}\n(dry run, {} not modified)\n\n{}",
+            message,
+            path.display(),
+            content.trim_end()
+        ));
+    }
+
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut message = message;
+    if let Some(original) = original {
+        let backup = backup_path(path);
+        if backup.exists() {
+            message.push_str(&format!("\noriginal kept in {}", backup.display()));
+        } else {
+            fs::write(&backup, original)?;
+            message.push_str(&format!("\nbackup saved to {}", backup.display()));
+        }
+    }
+
+    let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    fs::write(&tmp, content)?;
+    if let Err(e) = fs::rename(&tmp, path) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    Ok(message)
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// 取第一行缩进作为缩进单位，没有缩进时使用两个空格
+fn detect_indent(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+        .to_string()
+}
+
+fn to_string_with_indent<T: Serialize>(value: &T, indent: &str) -> serde_json::Result<String> {
+    let mut buffer = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+    value.serialize(&mut serializer)?;
+    Ok(String::from_utf8(buffer).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{command_program, shell_quote};
+
+    #[test]
+    fn plain_paths_are_not_quoted() {
+        assert_eq!(
+            shell_quote("/home/me/.claude/ccline/ccline"),
+            "/home/me/.claude/ccline/ccline"
+        );
+    }
+
+    #[test]
+    fn quoted_paths_round_trip() {
+        for path in [
+            "/Users/me/Library/Application Support/ccline/ccline",
+            "/opt/it's here/ccline",
+        ] {
+            let quoted = shell_quote(path);
+            assert_ne!(quoted, path);
+            assert_eq!(command_program(&format!("{} --theme light", quoted)), path);
+        }
+    }
+
+    #[test]
+    fn program_is_the_first_word() {
+        assert_eq!(command_program("  ccline --theme light"), "ccline");
+        assert_eq!(command_program("\"/a b/ccline\" -o plain"), "/a b/ccline");
+    }
+}
@@ -1,4 +1,25 @@
+pub mod blocks;
+pub mod budget;
+pub mod cache;
+pub mod claude;
+pub mod daily;
+pub mod doctor;
+pub mod format;
+pub mod history;
+pub mod index;
+pub mod install;
+pub mod models;
+pub mod output;
+pub mod pricing;
+pub mod project;
+pub mod quota;
+pub mod record;
+pub mod report;
+pub mod sample;
 pub mod segments;
 pub mod statusline;
+pub mod theme;
+pub mod transcript;
 
+pub use output::OutputMode;
 pub use statusline::StatusLineGenerator;
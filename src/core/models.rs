@@ -0,0 +1,382 @@
+use crate::config::{InputData, Model, ModelSpec, Pricing};
+use crate::core::output::Color;
+use std::collections::BTreeMap;
+
+const ONE_MILLION: u32 = 1_000_000;
+pub const DEFAULT_CONTEXT_WINDOW: u32 = 200_000;
+
+struct BuiltinModel {
+    patterns: &'static [&'static str],
+    name: &'static str,
+    family: &'static str,
+    pricing: Pricing,
+}
+
+const OPUS_4_5_PRICING: Pricing = Pricing {
+    input: 5.0,
+    output: 25.0,
+    cache_write: 6.25,
+    cache_read: 0.5,
+};
+
+const OPUS_PRICING: Pricing = Pricing {
+    input: 15.0,
+    output: 75.0,
+    cache_write: 18.75,
+    cache_read: 1.5,
+};
+
+const SONNET_PRICING: Pricing = Pricing {
+    input: 3.0,
+    output: 15.0,
+    cache_write: 3.75,
+    cache_read: 0.3,
+};
+
+const HAIKU_4_5_PRICING: Pricing = Pricing {
+    input: 1.0,
+    output: 5.0,
+    cache_write: 1.25,
+    cache_read: 0.1,
+};
+
+const HAIKU_3_5_PRICING: Pricing = Pricing {
+    input: 0.8,
+    output: 4.0,
+    cache_write: 1.0,
+    cache_read: 0.08,
+};
+
+const HAIKU_3_PRICING: Pricing = Pricing {
+    input: 0.25,
+    output: 1.25,
+    cache_write: 0.3,
+    cache_read: 0.03,
+};
+
+// 内置模型表，按顺序匹配，更具体的模式放在前面
+const BUILTIN_MODELS: &[BuiltinModel] = &[
+    BuiltinModel {
+        patterns: &["*opus-4-5*", "*opus 4.5*"],
+        name: "Opus 4.5",
+        family: "opus",
+        pricing: OPUS_4_5_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["*opus-4-1*", "claude-4-1-opus*", "*opus 4.1*"],
+        name: "Opus 4.1",
+        family: "opus",
+        pricing: OPUS_PRICING,
+    },
+    BuiltinModel {
+        patterns: &[
+            "*opus-4-20*",
+            "*opus-4",
+            "claude-4-opus*",
+            "opus 4",
+            "claude opus 4",
+        ],
+        name: "Opus 4",
+        family: "opus",
+        pricing: OPUS_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["claude-3-opus*", "opus 3", "claude opus 3"],
+        name: "Opus 3",
+        family: "opus",
+        pricing: OPUS_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["*sonnet-4-5*", "*sonnet 4.5*"],
+        name: "Sonnet 4.5",
+        family: "sonnet",
+        pricing: SONNET_PRICING,
+    },
+    BuiltinModel {
+        patterns: &[
+            "*sonnet-4-20*",
+            "*sonnet-4",
+            "claude-4-sonnet*",
+            "sonnet 4",
+            "claude sonnet 4",
+        ],
+        name: "Sonnet 4",
+        family: "sonnet",
+        pricing: SONNET_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["claude-3-7-sonnet*", "*sonnet 3.7*"],
+        name: "Sonnet 3.7",
+        family: "sonnet",
+        pricing: SONNET_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["claude-3-5-sonnet*", "*sonnet 3.5*"],
+        name: "Sonnet 3.5",
+        family: "sonnet",
+        pricing: SONNET_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["claude-3-sonnet*", "sonnet 3", "claude sonnet 3"],
+        name: "Sonnet 3",
+        family: "sonnet",
+        pricing: SONNET_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["*haiku-4-5*", "*haiku 4.5*"],
+        name: "Haiku 4.5",
+        family: "haiku",
+        pricing: HAIKU_4_5_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["claude-3-5-haiku*", "*haiku 3.5*"],
+        name: "Haiku 3.5",
+        family: "haiku",
+        pricing: HAIKU_3_5_PRICING,
+    },
+    BuiltinModel {
+        patterns: &["claude-3-haiku*", "haiku 3", "claude haiku 3"],
+        name: "Haiku 3",
+        family: "haiku",
+        pricing: HAIKU_3_PRICING,
+    },
+];
+
+impl From<&BuiltinModel> for ModelSpec {
+    fn from(model: &BuiltinModel) -> Self {
+        ModelSpec {
+            patterns: model.patterns.iter().map(|p| p.to_string()).collect(),
+            name: model.name.to_string(),
+            family: Some(model.family.to_string()),
+            icon: None,
+            color: None,
+            context_window: Some(DEFAULT_CONTEXT_WINDOW),
+            pricing: Some(model.pricing),
+        }
+    }
+}
+
+/// 解析后的模型信息
+#[derive(Debug, Clone)]
+pub struct ModelInfo {
+    pub name: String,
+    pub family: Option<String>,
+    pub icon: Option<String>,
+    pub color: Option<Color>,
+    pub context_window: Option<u32>,
+    pub pricing: Option<Pricing>,
+}
+
+/// 模型注册表：用户配置的条目优先，其次是内置模型表
+#[derive(Debug, Clone)]
+pub struct ModelRegistry {
+    specs: Vec<ModelSpec>,
+}
+
+impl Default for ModelRegistry {
+    fn default() -> Self {
+        Self::new(&[])
+    }
+}
+
+impl ModelRegistry {
+    pub fn new(user_models: &[ModelSpec]) -> Self {
+        let mut specs = user_models.to_vec();
+        specs.extend(BUILTIN_MODELS.iter().map(ModelSpec::from));
+        Self { specs }
+    }
+
+    /// 依次用每个候选名称（model id、display_name）匹配注册表
+    pub fn lookup(&self, candidates: &[&str]) -> Option<&ModelSpec> {
+        let candidates: Vec<String> = candidates
+            .iter()
+            .map(|c| strip_context_suffix(c).to_lowercase())
+            .filter(|c| !c.is_empty())
+            .collect();
+
+        candidates.iter().find_map(|candidate| {
+            self.specs.iter().find(|spec| {
+                spec.patterns
+                    .iter()
+                    .any(|pattern| glob_match(&pattern.to_lowercase(), candidate))
+            })
+        })
+    }
+
+    /// 解析 Claude Code 传入的模型，优先使用稳定的 model.id
+    pub fn resolve_model(&self, model: &Model) -> ModelInfo {
+        match model.id.as_deref() {
+            Some(id) => self.resolve(&[id, &model.display_name]),
+            None => self.resolve(&[&model.display_name]),
+        }
+    }
+
+    /// 解析模型信息；未登记的模型从名称中推断出 "Family X.Y" 形式的短名，
+    /// 都推断不出时使用最后一个候选名称（display_name）
+    pub fn resolve(&self, candidates: &[&str]) -> ModelInfo {
+        let is_1m = candidates.iter().any(|c| has_1m_suffix(c));
+        let mut info = match self.lookup(candidates) {
+            Some(spec) => ModelInfo {
+                name: spec.name.clone(),
+                family: spec.family.clone(),
+                icon: spec.icon.clone(),
+                color: spec.color,
+                context_window: spec.context_window,
+                pricing: spec.pricing,
+            },
+            None => {
+                let fallback = candidates
+                    .iter()
+                    .find_map(|c| infer_short_name(strip_context_suffix(c)));
+                let (name, family) = match fallback {
+                    Some((name, family)) => (name, Some(family)),
+                    None => {
+                        let display = candidates.last().copied().unwrap_or_default();
+                        (strip_context_suffix(display).to_string(), None)
+                    }
+                };
+                ModelInfo {
+                    name,
+                    family,
+                    icon: None,
+                    color: None,
+                    context_window: None,
+                    pricing: None,
+                }
+            }
+        };
+
+        if is_1m {
+            info.name = format!("{} 1M", info.name);
+            info.context_window = Some(ONE_MILLION);
+        }
+        info
+    }
+}
+
+/// 上下文窗口大小：Claude Code 报告的值 > 配置中按 model id 覆盖的值 > 模型注册表 > 200K
+pub fn resolve_context_window(
+    input: &InputData,
+    registry: &ModelRegistry,
+    overrides: &BTreeMap<String, u32>,
+) -> u32 {
+    if let Some(size) = input
+        .context_window
+        .as_ref()
+        .and_then(|cw| cw.context_window_size)
+    {
+        return size;
+    }
+
+    let model = &input.model;
+    let candidates: Vec<String> = model
+        .id
+        .iter()
+        .chain(std::iter::once(&model.display_name))
+        .map(|c| c.to_lowercase())
+        .filter(|c| !c.is_empty())
+        .collect();
+    let overridden = overrides.iter().find_map(|(pattern, size)| {
+        let pattern = pattern.to_lowercase();
+        candidates
+            .iter()
+            .any(|c| glob_match(&pattern, c))
+            .then_some(*size)
+    });
+
+    overridden
+        .or_else(|| registry.resolve_model(model).context_window)
+        .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+}
+
+/// 是否是 1M 上下文版本：display_name 带 "(1M context)"，或 model id 带 "[1m]"
+pub fn has_1m_suffix(name: &str) -> bool {
+    let lower = name.to_lowercase();
+    lower.contains("(1m context)") || lower.contains("[1m]")
+}
+
+fn strip_context_suffix(name: &str) -> &str {
+    let lower = name.to_ascii_lowercase();
+    let end = ["(1m context)", "[1m]"]
+        .iter()
+        .filter_map(|suffix| lower.find(suffix))
+        .min()
+        .unwrap_or(name.len());
+    name[..end].trim()
+}
+
+/// 从 claude-opus-4-6-20260101、claude-3-5-haiku 或 "Opus 4.6" 这类名称推断短名和系列
+fn infer_short_name(name: &str) -> Option<(String, String)> {
+    let lower = name.to_lowercase();
+    let tokens: Vec<&str> = lower
+        .split(['-', ' ', '.', '_'])
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    let family = tokens
+        .iter()
+        .find(|t| matches!(**t, "opus" | "sonnet" | "haiku"))?;
+    // 版本号只取 1~2 位数字，跳过 20250514 这类日期
+    let version: Vec<&str> = tokens
+        .iter()
+        .filter(|t| t.len() <= 2 && t.chars().all(|c| c.is_ascii_digit()))
+        .copied()
+        .collect();
+
+    let mut short = family[..1].to_uppercase() + &family[1..];
+    if !version.is_empty() {
+        short = format!("{} {}", short, version.join("."));
+    }
+    Some((short, family.to_string()))
+}
+
+/// 简单的通配符匹配，仅支持 *
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+
+    let first = parts[0];
+    let last = parts[parts.len() - 1];
+    // 先去掉首尾再切片，避免在多字节字符中间截断
+    let Some(rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let Some(mut rest) = rest.strip_suffix(last) else {
+        return false;
+    };
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    #[test]
+    fn matches_wildcards() {
+        assert!(glob_match("*opus-4*", "claude-opus-4-1-20250805"));
+        assert!(glob_match("claude-*-4", "claude-opus-4"));
+        assert!(glob_match("a*b*c", "abc"));
+        assert!(!glob_match("a*b*c", "acb"));
+        assert!(!glob_match("ab*ba", "aba"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "exactly"));
+    }
+
+    #[test]
+    fn multibyte_text_does_not_panic() {
+        assert!(!glob_match("*opus-4", "模型A"));
+        assert!(!glob_match("opus*", "模型A"));
+        assert!(glob_match("模型*", "模型A"));
+        assert!(glob_match("*型A", "模型A"));
+        assert!(glob_match("模*A", "模型A"));
+        assert!(!glob_match("模型*型A", "模型A"));
+    }
+}
@@ -0,0 +1,491 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::env;
+
+/// 输出目标：Claude Code 使用的 ANSI，或者 tmux、shell prompt 等其他消费者
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    /// ANSI escape sequences (Claude Code statusline)
+    #[default]
+    Ansi,
+    /// Plain text without any styling
+    Plain,
+    /// Structured JSON with segment name, text and style
+    Json,
+    /// tmux format strings (`#[fg=...]`)
+    Tmux,
+    /// zsh prompt with escapes wrapped in `%{ %}`
+    Zsh,
+    /// bash prompt with escapes wrapped in `\[ \]`
+    Bash,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// 256 色调色板中的颜色
+    Fixed(u8),
+}
+
+impl Color {
+    fn ansi_code(&self) -> String {
+        match self {
+            Color::Black => "30".to_string(),
+            Color::Red => "31".to_string(),
+            Color::Green => "32".to_string(),
+            Color::Yellow => "33".to_string(),
+            Color::Blue => "34".to_string(),
+            Color::Magenta => "35".to_string(),
+            Color::Cyan => "36".to_string(),
+            Color::White => "37".to_string(),
+            Color::BrightBlack => "90".to_string(),
+            Color::BrightRed => "91".to_string(),
+            Color::BrightGreen => "92".to_string(),
+            Color::BrightYellow => "93".to_string(),
+            Color::BrightBlue => "94".to_string(),
+            Color::BrightMagenta => "95".to_string(),
+            Color::BrightCyan => "96".to_string(),
+            Color::BrightWhite => "97".to_string(),
+            Color::Fixed(n) => format!("38;5;{}", n),
+        }
+    }
+
+    /// 颜色名称，与 tmux 的颜色名保持一致
+    pub fn name(&self) -> String {
+        match self {
+            Color::Black => "black".to_string(),
+            Color::Red => "red".to_string(),
+            Color::Green => "green".to_string(),
+            Color::Yellow => "yellow".to_string(),
+            Color::Blue => "blue".to_string(),
+            Color::Magenta => "magenta".to_string(),
+            Color::Cyan => "cyan".to_string(),
+            Color::White => "white".to_string(),
+            Color::BrightBlack => "brightblack".to_string(),
+            Color::BrightRed => "brightred".to_string(),
+            Color::BrightGreen => "brightgreen".to_string(),
+            Color::BrightYellow => "brightyellow".to_string(),
+            Color::BrightBlue => "brightblue".to_string(),
+            Color::BrightMagenta => "brightmagenta".to_string(),
+            Color::BrightCyan => "brightcyan".to_string(),
+            Color::BrightWhite => "brightwhite".to_string(),
+            Color::Fixed(n) => format!("colour{}", n),
+        }
+    }
+}
+
+impl std::str::FromStr for Color {
+    type Err = String;
+
+    /// 接受 tmux 风格的颜色名（cyan、brightblack）以及 colour147 / 147 形式的 256 色
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase().replace(['_', '-'], "");
+        let color = match lower.as_str() {
+            "black" => Color::Black,
+            "red" => Color::Red,
+            "green" => Color::Green,
+            "yellow" => Color::Yellow,
+            "blue" => Color::Blue,
+            "magenta" => Color::Magenta,
+            "cyan" => Color::Cyan,
+            "white" => Color::White,
+            "brightblack" | "gray" | "grey" => Color::BrightBlack,
+            "brightred" => Color::BrightRed,
+            "brightgreen" => Color::BrightGreen,
+            "brightyellow" => Color::BrightYellow,
+            "brightblue" => Color::BrightBlue,
+            "brightmagenta" => Color::BrightMagenta,
+            "brightcyan" => Color::BrightCyan,
+            "brightwhite" => Color::BrightWhite,
+            other => {
+                let number = other
+                    .strip_prefix("colour")
+                    .or_else(|| other.strip_prefix("color"))
+                    .unwrap_or(other);
+                Color::Fixed(
+                    number
+                        .parse()
+                        .map_err(|_| format!("unknown color: {}", s))?,
+                )
+            }
+        };
+        Ok(color)
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bold: bool,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg(color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            bold: false,
+        }
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    fn is_plain(&self) -> bool {
+        self.fg.is_none() && !self.bold
+    }
+
+    fn ansi_codes(&self) -> String {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push("1".to_string());
+        }
+        if let Some(fg) = self.fg {
+            codes.push(fg.ansi_code());
+        }
+        codes.join(";")
+    }
+}
+
+/// 一段带样式的文本，可选地带有 OSC 8 超链接
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+impl Span {
+    pub fn styled(text: impl Into<String>, style: Style) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            link: None,
+        }
+    }
+
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::styled(text, Style::new())
+    }
+
+    pub fn with_link(mut self, link: Option<String>) -> Self {
+        self.link = link;
+        self
+    }
+}
+
+/// 单个 segment 的渲染结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedSegment {
+    pub name: &'static str,
+    pub text: String,
+    pub spans: Vec<Span>,
+}
+
+impl RenderedSegment {
+    pub fn new(name: &'static str, spans: Vec<Span>) -> Self {
+        let text = spans.iter().map(|s| s.text.as_str()).collect();
+        Self { name, text, spans }
+    }
+}
+
+const SEPARATOR: &str = " | ";
+
+impl OutputMode {
+    /// 将所有 segment 按当前输出模式拼接成最终字符串
+    pub fn format(&self, segments: &[RenderedSegment]) -> String {
+        self.format_with(segments, Style::fg(Color::White))
+    }
+
+    /// 同 format，使用指定的分隔符样式
+    pub fn format_with(&self, segments: &[RenderedSegment], separator: Style) -> String {
+        if *self == OutputMode::Json {
+            return serde_json::to_string(segments).unwrap_or_default();
+        }
+
+        let separator = self.format_span(&Span::styled(SEPARATOR, separator));
+        segments
+            .iter()
+            .map(|segment| {
+                segment
+                    .spans
+                    .iter()
+                    .filter(|span| !span.text.is_empty())
+                    .map(|span| self.format_span(span))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join(&separator)
+    }
+
+    /// 该输出模式能否承载 OSC 8 超链接（JSON 以字段形式输出链接）
+    pub fn supports_hyperlinks(&self) -> bool {
+        matches!(
+            self,
+            OutputMode::Ansi | OutputMode::Zsh | OutputMode::Bash | OutputMode::Json
+        )
+    }
+
+    fn format_span(&self, span: &Span) -> String {
+        let styled = self.format_styled(span);
+        match &span.link {
+            Some(link) => self.wrap_link(link, styled),
+            None => styled,
+        }
+    }
+
+    fn wrap_link(&self, link: &str, text: String) -> String {
+        let link = self.escape(link);
+        match self {
+            OutputMode::Ansi => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", link, text),
+            OutputMode::Zsh => format!("%{{\x1b]8;;{}\x1b\\%}}{}%{{\x1b]8;;\x1b\\%}}", link, text),
+            // bash 会解释 prompt 中的反斜杠，因此使用 BEL 作为终止符而不是 ESC \
+            OutputMode::Bash => format!("\\[\x1b]8;;{}\x07\\]{}\\[\x1b]8;;\x07\\]", link, text),
+            _ => text,
+        }
+    }
+
+    fn format_styled(&self, span: &Span) -> String {
+        let text = self.escape(&span.text);
+        if span.style.is_plain() {
+            return text;
+        }
+
+        match self {
+            OutputMode::Ansi => format!("\x1b[{}m{}\x1b[0m", span.style.ansi_codes(), text),
+            OutputMode::Zsh => format!(
+                "%{{\x1b[{}m%}}{}%{{\x1b[0m%}}",
+                span.style.ansi_codes(),
+                text
+            ),
+            OutputMode::Bash => format!(
+                "\\[\x1b[{}m\\]{}\\[\x1b[0m\\]",
+                span.style.ansi_codes(),
+                text
+            ),
+            OutputMode::Tmux => {
+                let mut attrs = Vec::new();
+                if let Some(fg) = span.style.fg {
+                    attrs.push(format!("fg={}", fg.name()));
+                }
+                if span.style.bold {
+                    attrs.push("bold".to_string());
+                }
+                format!("#[{}]{}#[default]", attrs.join(","), text)
+            }
+            OutputMode::Plain | OutputMode::Json => text,
+        }
+    }
+
+    /// 转义对目标格式有特殊含义的字符
+    fn escape(&self, text: &str) -> String {
+        match self {
+            OutputMode::Tmux => text.replace('#', "##"),
+            OutputMode::Zsh => text.replace('%', "%%"),
+            OutputMode::Bash => text
+                .replace('\\', "\\\\")
+                .replace('$', "\\$")
+                .replace('`', "\\`"),
+            _ => text.to_string(),
+        }
+    }
+}
+
+/// 检测当前终端是否支持 OSC 8 超链接，可通过 FORCE_HYPERLINK 强制开关
+pub fn terminal_supports_hyperlinks() -> bool {
+    if let Ok(force) = env::var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+
+    let term = env::var("TERM").unwrap_or_default();
+    if term == "dumb" {
+        return false;
+    }
+
+    if env::var_os("WT_SESSION").is_some() || env::var_os("KITTY_WINDOW_ID").is_some() {
+        return true;
+    }
+
+    let version_at_least = |name: &str, min: u32| {
+        env::var(name)
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .is_some_and(|v| v >= min)
+    };
+    if version_at_least("VTE_VERSION", 5000) || version_at_least("KONSOLE_VERSION", 201200) {
+        return true;
+    }
+
+    let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+    if matches!(
+        term_program.as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper" | "Tabby" | "rio"
+    ) {
+        return true;
+    }
+
+    ["kitty", "alacritty", "foot", "wezterm", "ghostty"]
+        .iter()
+        .any(|name| term.contains(name))
+}
+
+/// 将本地路径转换为 file:// URL
+pub fn file_url(path: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    if normalized.starts_with('/') {
+        format!("file://{}", encode_url_path(&normalized))
+    } else {
+        // Windows 盘符路径，如 C:/Users
+        format!("file:///{}", encode_url_path(&normalized))
+    }
+}
+
+/// 对 URL 路径做百分号编码，保留路径分隔符
+pub fn encode_url_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'/'
+            | b':'
+            | b'@'
+            | b'+'
+            | b','
+            | b'='
+            | b'!' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_url_path, Color, OutputMode, RenderedSegment, Span, Style};
+
+    fn segments() -> Vec<RenderedSegment> {
+        vec![
+            RenderedSegment::new(
+                "cost",
+                vec![Span::styled("$5 100% #1 `x`", Style::fg(Color::Red).bold())],
+            ),
+            RenderedSegment::new("model", vec![Span::plain("Opus")]),
+        ]
+    }
+
+    #[test]
+    fn plain_joins_segments() {
+        assert_eq!(
+            OutputMode::Plain.format(&segments()),
+            "$5 100% #1 `x` | Opus"
+        );
+    }
+
+    #[test]
+    fn ansi_wraps_styles() {
+        assert_eq!(
+            OutputMode::Ansi.format(&segments()),
+            "\x1b[1;31m$5 100% #1 `x`\x1b[0m\x1b[37m | \x1b[0mOpus"
+        );
+    }
+
+    #[test]
+    fn tmux_escapes_hashes() {
+        assert_eq!(
+            OutputMode::Tmux.format(&segments()),
+            "#[fg=red,bold]$5 100% ##1 `x`#[default]#[fg=white] | #[default]Opus"
+        );
+    }
+
+    #[test]
+    fn zsh_escapes_percent_and_marks_zero_width() {
+        assert_eq!(
+            OutputMode::Zsh.format(&segments()),
+            "%{\x1b[1;31m%}$5 100%% #1 `x`%{\x1b[0m%}%{\x1b[37m%} | %{\x1b[0m%}Opus"
+        );
+    }
+
+    #[test]
+    fn bash_escapes_expansions_and_marks_zero_width() {
+        assert_eq!(
+            OutputMode::Bash.format(&segments()),
+            "\\[\x1b[1;31m\\]\\$5 100% #1 \\`x\\`\\[\x1b[0m\\]\\[\x1b[37m\\] | \\[\x1b[0m\\]Opus"
+        );
+    }
+
+    #[test]
+    fn json_keeps_names_styles_and_links() {
+        let mut segments = segments();
+        segments[1].spans[0].link = Some("https://example.com".to_string());
+        let value: serde_json::Value =
+            serde_json::from_str(&OutputMode::Json.format(&segments)).unwrap();
+        assert_eq!(value[0]["name"], "cost");
+        assert_eq!(value[0]["text"], "$5 100% #1 `x`");
+        assert_eq!(value[0]["spans"][0]["style"]["bold"], true);
+        assert_eq!(value[1]["spans"][0]["link"], "https://example.com");
+        assert!(value[0]["spans"][0].get("link").is_none());
+    }
+
+    #[test]
+    fn links_use_osc8() {
+        let segments = [RenderedSegment::new(
+            "directory",
+            vec![Span::plain("repo").with_link(Some("file:///a%20b".to_string()))],
+        )];
+        assert_eq!(
+            OutputMode::Ansi.format(&segments),
+            "\x1b]8;;file:///a%20b\x1b\\repo\x1b]8;;\x1b\\"
+        );
+        assert_eq!(
+            OutputMode::Bash.format(&segments),
+            "\\[\x1b]8;;file:///a%20b\x07\\]repo\\[\x1b]8;;\x07\\]"
+        );
+        assert_eq!(OutputMode::Tmux.format(&segments), "repo");
+    }
+
+    #[test]
+    fn url_paths_are_percent_encoded() {
+        assert_eq!(encode_url_path("/a b/中#?"), "/a%20b/%E4%B8%AD%23%3F");
+    }
+}
@@ -0,0 +1,157 @@
+use crate::config::{CostConfig, Pricing};
+use crate::core::models::ModelRegistry;
+use crate::core::transcript::{TokenUsage, UsageEntry};
+use std::collections::BTreeMap;
+use std::env;
+
+const PER_MILLION: f64 = 1_000_000.0;
+
+/// 按类别拆分的费用（美元）
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostBreakdown {
+    pub input: f64,
+    pub output: f64,
+    pub cache_write: f64,
+    pub cache_read: f64,
+}
+
+impl CostBreakdown {
+    pub fn from_usage(usage: &TokenUsage, pricing: &Pricing) -> Self {
+        Self {
+            input: usage.input_tokens as f64 * pricing.input / PER_MILLION,
+            output: usage.output_tokens as f64 * pricing.output / PER_MILLION,
+            cache_write: usage.cache_creation_input_tokens as f64 * pricing.cache_write
+                / PER_MILLION,
+            cache_read: usage.cache_read_input_tokens as f64 * pricing.cache_read / PER_MILLION,
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.input + self.output + self.cache_write + self.cache_read
+    }
+
+    pub fn add(&mut self, other: &CostBreakdown) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_write += other.cache_write;
+        self.cache_read += other.cache_read;
+    }
+
+    pub fn scale(&self, factor: f64) -> Self {
+        Self {
+            input: self.input * factor,
+            output: self.output * factor,
+            cache_write: self.cache_write * factor,
+            cache_read: self.cache_read * factor,
+        }
+    }
+}
+
+/// 整个会话的费用汇总
+#[derive(Debug, Clone, Default)]
+pub struct SessionCost {
+    pub total: CostBreakdown,
+    pub usage: TokenUsage,
+    pub by_model: BTreeMap<String, CostBreakdown>,
+    /// 在模型注册表中没有价格的模型，其用量不计入费用
+    pub unpriced_models: Vec<String>,
+}
+
+/// 根据模型注册表的价格和服务商倍率计算费用
+#[derive(Debug, Clone)]
+pub struct CostEngine {
+    registry: ModelRegistry,
+    multiplier: f64,
+}
+
+impl CostEngine {
+    pub fn new(registry: ModelRegistry, multiplier: f64) -> Self {
+        Self {
+            registry,
+            multiplier,
+        }
+    }
+
+    pub fn from_config(registry: ModelRegistry, config: &CostConfig) -> Self {
+        Self::new(registry, provider_multiplier(config))
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    pub fn pricing_for(&self, model: &str) -> Option<Pricing> {
+        self.registry.resolve(&[model]).pricing
+    }
+
+    /// 单条记录的费用，模型没有价格时返回 None
+    pub fn entry_cost(&self, entry: &UsageEntry) -> Option<CostBreakdown> {
+        let pricing = self.pricing_for(&entry.model)?;
+        Some(CostBreakdown::from_usage(&entry.usage, &pricing).scale(self.multiplier))
+    }
+
+    /// 估算缓存节省的费用：缓存读取相对原价输入的节省，减去缓存写入的额外开销
+    pub fn cache_savings(&self, entries: &[UsageEntry]) -> f64 {
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let pricing = self.pricing_for(&entry.model)?;
+                let usage = &entry.usage;
+                let saved = usage.cache_read_input_tokens as f64
+                    * (pricing.input - pricing.cache_read)
+                    - usage.cache_creation_input_tokens as f64
+                        * (pricing.cache_write - pricing.input);
+                Some(saved / PER_MILLION * self.multiplier)
+            })
+            .sum()
+    }
+
+    pub fn session_cost(&self, entries: &[UsageEntry]) -> SessionCost {
+        let mut session = SessionCost::default();
+        for entry in entries {
+            session.usage.add(&entry.usage);
+            match self.entry_cost(entry) {
+                Some(cost) => {
+                    session.total.add(&cost);
+                    session
+                        .by_model
+                        .entry(self.registry.resolve(&[&entry.model]).name)
+                        .or_default()
+                        .add(&cost);
+                }
+                None => {
+                    if !session.unpriced_models.contains(&entry.model) {
+                        session.unpriced_models.push(entry.model.clone());
+                    }
+                }
+            }
+        }
+        session
+    }
+}
+
+/// 当前服务商的价格倍率：优先使用 cost.provider 指定的服务商，
+/// 否则用 ANTHROPIC_BASE_URL 匹配各服务商的 base_url，都不匹配时为 1.0
+pub fn provider_multiplier(config: &CostConfig) -> f64 {
+    if let Some(name) = &config.provider {
+        return config
+            .providers
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .map_or(1.0, |p| p.multiplier);
+    }
+
+    let base_url = env::var("ANTHROPIC_BASE_URL").unwrap_or_default();
+    if base_url.is_empty() {
+        return 1.0;
+    }
+    config
+        .providers
+        .iter()
+        .find(|p| {
+            p.base_url
+                .as_deref()
+                .is_some_and(|url| !url.is_empty() && base_url.contains(url))
+        })
+        .map_or(1.0, |p| p.multiplier)
+}
@@ -0,0 +1,194 @@
+use crate::core::cache;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const CACHE_FILE: &str = "projects.json";
+const CACHE_LIMIT: usize = 256;
+
+/// 项目类型，由目录中的清单文件决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectKind {
+    Rust,
+    Python,
+    Go,
+    Node,
+    Deno,
+    Php,
+    Ruby,
+}
+
+impl ProjectKind {
+    /// Nerd Font 语言图标
+    pub fn icon(&self) -> &'static str {
+        match self {
+            ProjectKind::Rust => "\u{e7a8}",
+            ProjectKind::Python => "\u{e73c}",
+            ProjectKind::Go => "\u{e627}",
+            ProjectKind::Node => "\u{e718}",
+            ProjectKind::Deno => "\u{e7c0}",
+            ProjectKind::Php => "\u{e73d}",
+            ProjectKind::Ruby => "\u{e739}",
+        }
+    }
+}
+
+/// 同一目录存在多个清单时按此顺序优先匹配
+const MARKERS: &[(&str, ProjectKind)] = &[
+    ("Cargo.toml", ProjectKind::Rust),
+    ("pyproject.toml", ProjectKind::Python),
+    ("go.mod", ProjectKind::Go),
+    ("deno.json", ProjectKind::Deno),
+    ("package.json", ProjectKind::Node),
+    ("composer.json", ProjectKind::Php),
+    ("Gemfile", ProjectKind::Ruby),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    pub kind: ProjectKind,
+    pub name: String,
+    pub root: PathBuf,
+    manifest: PathBuf,
+    modified_ms: u64,
+}
+
+/// 缓存的检测结果，没有找到项目时也缓存，在 NEGATIVE_TTL_SECS 后重新查找
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    project: Option<ProjectInfo>,
+    checked_at: u64,
+}
+
+/// 没有找到项目的目录在这段时间内不再重复查找
+const NEGATIVE_TTL_SECS: u64 = 60;
+
+/// 从 current_dir 向上查找最近的项目清单，结果按目录缓存，
+/// 在清单修改或 current_dir 与项目根目录之间出现新的清单后失效
+pub fn detect(current_dir: &Path) -> Option<ProjectInfo> {
+    let key = current_dir.to_string_lossy().to_string();
+    let mut entries: BTreeMap<String, CacheEntry> = cache::load(CACHE_FILE);
+    let now = now_secs();
+
+    if let Some(entry) = entries.get(&key) {
+        match &entry.project {
+            Some(info) if is_fresh(current_dir, info) => return Some(info.clone()),
+            None if now.saturating_sub(entry.checked_at) < NEGATIVE_TTL_SECS => return None,
+            _ => {}
+        }
+    }
+
+    let project = find_project(current_dir);
+    cache::evict_oldest(
+        &mut entries,
+        CACHE_LIMIT,
+        |entry| entry.checked_at,
+        |_| false,
+    );
+    entries.insert(
+        key,
+        CacheEntry {
+            project: project.clone(),
+            checked_at: now,
+        },
+    );
+    cache::store(CACHE_FILE, &entries);
+    project
+}
+
+/// 缓存的项目仍然有效：清单没有修改，且更近的目录或根目录中没有出现优先级更高的清单
+fn is_fresh(current_dir: &Path, info: &ProjectInfo) -> bool {
+    if modified_ms(&info.manifest) != Some(info.modified_ms) {
+        return false;
+    }
+    for dir in current_dir.ancestors() {
+        if dir == info.root {
+            return MARKERS
+                .iter()
+                .take_while(|(_, kind)| *kind != info.kind)
+                .all(|(marker, _)| !dir.join(marker).exists());
+        }
+        if MARKERS.iter().any(|(marker, _)| dir.join(marker).exists()) {
+            return false;
+        }
+    }
+    false
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn find_project(current_dir: &Path) -> Option<ProjectInfo> {
+    let home = dirs::home_dir();
+    for dir in current_dir.ancestors() {
+        for (marker, kind) in MARKERS {
+            let manifest = dir.join(marker);
+            let Some(modified_ms) = modified_ms(&manifest) else {
+                continue;
+            };
+
+            let name = read_project_name(&manifest, *kind).unwrap_or_else(|| {
+                dir.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+            return Some(ProjectInfo {
+                kind: *kind,
+                name,
+                root: dir.to_path_buf(),
+                manifest,
+                modified_ms,
+            });
+        }
+
+        // 不越过 home 目录，避免匹配到 ~ 下的全局清单
+        if home.as_deref() == Some(dir) {
+            break;
+        }
+    }
+    None
+}
+
+fn modified_ms(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_millis() as u64)
+}
+
+fn read_project_name(manifest: &Path, kind: ProjectKind) -> Option<String> {
+    let content = fs::read_to_string(manifest).ok()?;
+    let name = match kind {
+        ProjectKind::Rust => {
+            let value: toml::Value = toml::from_str(&content).ok()?;
+            value.get("package")?.get("name")?.as_str()?.to_string()
+        }
+        ProjectKind::Python => {
+            let value: toml::Value = toml::from_str(&content).ok()?;
+            value
+                .get("project")
+                .or_else(|| value.get("tool")?.get("poetry"))?
+                .get("name")?
+                .as_str()?
+                .to_string()
+        }
+        ProjectKind::Go => {
+            // module github.com/owner/repo -> repo
+            let module = content
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("module "))?;
+            module.trim().rsplit('/').next()?.to_string()
+        }
+        ProjectKind::Node | ProjectKind::Deno | ProjectKind::Php => {
+            let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+            value.get("name")?.as_str()?.to_string()
+        }
+        ProjectKind::Ruby => return None,
+    };
+
+    (!name.is_empty()).then_some(name)
+}
@@ -0,0 +1,141 @@
+use crate::core::cache;
+use crate::core::claude;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const CACHE_FILE: &str = "quota.json";
+const DEFAULT_INFO_URL: &str = "https://www.packycode.com/api/backend/users/info";
+/// 额度对应的网页控制台，预算 segment 使用 PackyCode 额度时链接到这里
+pub const DASHBOARD_URL: &str = "https://www.packycode.com/dashboard";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// PackyCode 账户的日/月额度和已用金额（美元）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Quota {
+    pub daily_spent: Option<f64>,
+    pub daily_budget: Option<f64>,
+    pub monthly_spent: Option<f64>,
+    pub monthly_budget: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CachedQuota {
+    fetched_at: u64,
+    quota: Option<Quota>,
+}
+
+/// 读取 PackyCode 额度，每 ttl_secs 秒最多请求一次；请求失败时沿用上次的结果
+pub fn fetch(ttl_secs: u64) -> Option<Quota> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let cached: CachedQuota = cache::load(CACHE_FILE);
+    if now.saturating_sub(cached.fetched_at) < ttl_secs {
+        return cached.quota;
+    }
+
+    // 失败时也记录请求时间，避免每次渲染都等待超时
+    let quota = request().or(cached.quota);
+    cache::store(
+        CACHE_FILE,
+        &CachedQuota {
+            fetched_at: now,
+            quota,
+        },
+    );
+    quota
+}
+
+/// PackyCode 的访问凭据
+#[derive(Debug, Clone)]
+pub struct Credentials {
+    pub token: String,
+    pub info_url: String,
+}
+
+/// 从环境变量和 Claude Code 的 settings.json 中读取凭据，没有 token 时返回 None
+///
+/// ANTHROPIC_API_KEY 可能是 Anthropic 官方的 key，只有 ANTHROPIC_BASE_URL 指向 PackyCode 时才使用
+pub fn credentials() -> Option<Credentials> {
+    let settings = read_settings();
+    let packycode = env_or_setting("ANTHROPIC_BASE_URL", &settings)
+        .is_some_and(|url| url.to_lowercase().contains("packycode"));
+    let token = env_or_setting("ANTHROPIC_AUTH_TOKEN", &settings).or_else(|| {
+        packycode
+            .then(|| env_or_setting("ANTHROPIC_API_KEY", &settings))
+            .flatten()
+    })?;
+    let info_url = settings
+        .iter()
+        .find_map(|s| s.get("info_url").and_then(Value::as_str))
+        .unwrap_or(DEFAULT_INFO_URL)
+        .to_string();
+    Some(Credentials { token, info_url })
+}
+
+fn request() -> Option<Quota> {
+    request_with(&credentials()?).ok()
+}
+
+/// 请求额度接口，失败时返回错误说明
+pub fn request_with(credentials: &Credentials) -> Result<Quota, String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(&credentials.info_url)
+        .bearer_auth(&credentials.token)
+        .send()
+        .map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status));
+    }
+    let body: Value = response.json().map_err(|e| e.to_string())?;
+    // 部分接口把数据包在 data 字段中
+    let data = body.get("data").unwrap_or(&body);
+    Ok(Quota {
+        daily_spent: number(data, "daily_spent_usd"),
+        daily_budget: number(data, "daily_budget_usd"),
+        monthly_spent: number(data, "monthly_spent_usd"),
+        monthly_budget: number(data, "monthly_budget_usd"),
+    })
+}
+
+/// 金额可能是数字也可能是字符串
+fn number(data: &Value, key: &str) -> Option<f64> {
+    match data.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Claude Code 的用户级 settings.json
+fn read_settings() -> Vec<Value> {
+    claude::user_settings_files()
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect()
+}
+
+/// 优先读取环境变量，其次读取 settings.json 中 env 下的同名配置
+fn env_or_setting(name: &str, settings: &[Value]) -> Option<String> {
+    env::var(name)
+        .ok()
+        .filter(|value| !value.is_empty())
+        .or_else(|| {
+            settings.iter().find_map(|s| {
+                s.get("env")
+                    .and_then(|env| env.get(name))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            })
+        })
+}
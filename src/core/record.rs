@@ -0,0 +1,348 @@
+use crate::config::{Config, ConfigLoader, InputData, RecordConfig};
+use crate::core::output::{OutputMode, RenderedSegment};
+use crate::core::StatusLineGenerator;
+use chrono::{Local, SecondsFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 一次渲染的记录：Claude Code 传入的原始 JSON 和当时的输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recording {
+    /// 记录时间（RFC 3339），直接保存的输入文件没有该字段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recorded_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ccline_version: Option<String>,
+    pub input: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// 记录时的输出模式，回放比较时使用同一模式渲染
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mode: Option<OutputMode>,
+    /// 各 segment 的纯文本，用于 --stable 比较
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<RecordedSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedSegment {
+    pub name: String,
+    pub text: String,
+}
+
+/// 记录文件路径，默认为 ~/.claude/ccline/records.jsonl
+pub fn record_path(config: &RecordConfig) -> Option<PathBuf> {
+    match &config.path {
+        Some(path) => Some(PathBuf::from(path)),
+        None => ConfigLoader::ccline_dir().map(|dir| dir.join("records.jsonl")),
+    }
+}
+
+/// 追加一条记录，写入失败不影响 statusline 输出
+pub fn record(
+    raw: &str,
+    output_mode: OutputMode,
+    segments: &[RenderedSegment],
+    output: &str,
+    config: &RecordConfig,
+) {
+    if !config.enabled {
+        return;
+    }
+    let Some(path) = record_path(config) else {
+        return;
+    };
+    let Ok(input) = serde_json::from_str(raw) else {
+        return;
+    };
+    let recording = Recording {
+        recorded_at: Some(Local::now().to_rfc3339_opts(SecondsFormat::Millis, false)),
+        ccline_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        input,
+        output: Some(output.to_string()),
+        output_mode: Some(output_mode),
+        segments: segments
+            .iter()
+            .map(|segment| RecordedSegment {
+                name: segment.name.to_string(),
+                text: segment.text.clone(),
+            })
+            .collect(),
+    };
+    let _ = append(&path, &recording, config);
+}
+
+fn append(path: &Path, recording: &Recording, config: &RecordConfig) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut line = serde_json::to_string(recording)?;
+    line.push('\n');
+    let len = fs::metadata(path).map_or(0, |meta| meta.len());
+    if len > 0 && len + line.len() as u64 > config.max_bytes {
+        rotate(path, config.max_files)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// records.jsonl -> records.jsonl.1 -> records.jsonl.2 ...，超出 max_files 的最旧文件被删除
+fn rotate(path: &Path, max_files: u32) -> io::Result<()> {
+    let rotated = |n: u32| {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    };
+
+    if max_files == 0 {
+        return fs::remove_file(path);
+    }
+    let _ = fs::remove_file(rotated(max_files));
+    for n in (1..max_files).rev() {
+        let from = rotated(n);
+        if from.exists() {
+            fs::rename(&from, rotated(n + 1))?;
+        }
+    }
+    fs::rename(path, rotated(1))
+}
+
+/// 读取记录文件；也接受单个输入 JSON 或每行一个输入的文件，方便用保存的输入做回归用例
+pub fn read_recordings(path: &Path) -> io::Result<Vec<Recording>> {
+    let content = fs::read_to_string(path)?;
+    if let Ok(value) = serde_json::from_str::<Value>(&content) {
+        return Ok(vec![to_recording(value)]);
+    }
+
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(number, line)| {
+            serde_json::from_str(line).map(to_recording).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} line {}: {}", path.display(), number + 1, e),
+                )
+            })
+        })
+        .collect()
+}
+
+fn to_recording(value: Value) -> Recording {
+    if value.get("input").is_some_and(Value::is_object) {
+        if let Ok(recording) = serde_json::from_value(value.clone()) {
+            return recording;
+        }
+    }
+    Recording {
+        recorded_at: None,
+        ccline_version: None,
+        input: value,
+        output: None,
+        output_mode: None,
+        segments: Vec::new(),
+    }
+}
+
+/// 回放结果：输出文本和与记录不一致的条数
+pub struct Replay {
+    pub text: String,
+    pub changed: usize,
+}
+
+/// 用当前配置重新渲染每条记录；diff 为 true 时只输出与记录不同的条目。
+/// 比较时使用记录的输出模式，stable 为 true 时改为逐个比较稳定 segment 的纯文本
+pub fn replay(
+    recordings: &[Recording],
+    config: &Config,
+    output: OutputMode,
+    diff: bool,
+    stable: bool,
+) -> Replay {
+    let generator = |output: OutputMode| {
+        StatusLineGenerator::new(config.clone())
+            .with_output(output)
+            .with_alerts(false)
+    };
+    // 依赖时钟、git 或输入以外文件的 segment，由各 segment 自己声明
+    let volatile = generator(OutputMode::Plain).volatile_segments();
+    let mut lines = Vec::new();
+    let mut changed = 0;
+
+    for (index, recording) in recordings.iter().enumerate() {
+        let label = format!(
+            "#{}{}",
+            index + 1,
+            recording
+                .recorded_at
+                .as_deref()
+                .map(|time| format!(" {}", time))
+                .unwrap_or_default()
+        );
+        let input: InputData = match serde_json::from_value(recording.input.clone()) {
+            Ok(input) => input,
+            Err(e) => {
+                changed += 1;
+                lines.push(format!("{} invalid input: {}", label, e));
+                continue;
+            }
+        };
+
+        if !diff {
+            lines.push(generator(output).generate(&input));
+            continue;
+        }
+
+        let (previous, rendered) = if stable {
+            let segments: Vec<RecordedSegment> = generator(OutputMode::Plain)
+                .collect(&input)
+                .into_iter()
+                .map(|segment| RecordedSegment {
+                    name: segment.name.to_string(),
+                    text: segment.text,
+                })
+                .collect();
+            // 旧版本的记录没有逐个 segment 的文本，无法比较
+            let previous = (!recording.segments.is_empty())
+                .then(|| stable_text(&recording.segments, &volatile));
+            (previous, stable_text(&segments, &volatile))
+        } else {
+            let mode = recording.output_mode.unwrap_or(output);
+            (recording.output.clone(), generator(mode).generate(&input))
+        };
+        // 没有记录输出的条目无法比较，只显示新的结果
+        if previous.as_deref() != Some(rendered.as_str()) {
+            changed += 1;
+            lines.push(label);
+            if let Some(previous) = &previous {
+                lines.push(format!("- {}", previous));
+            }
+            lines.push(format!("+ {}", rendered));
+        }
+    }
+
+    if diff {
+        lines.push(format!(
+            "{} of {} recordings changed",
+            changed,
+            recordings.len()
+        ));
+    }
+    Replay {
+        text: lines.join("\n"),
+        changed,
+    }
+}
+
+/// 跳过易变 segment 后的纯文本
+fn stable_text(segments: &[RecordedSegment], volatile: &[&str]) -> String {
+    segments
+        .iter()
+        .filter(|segment| !volatile.contains(&segment.name.as_str()))
+        .map(|segment| segment.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{replay, rotate, RecordedSegment, Recording};
+    use crate::config::{Config, InputData};
+    use crate::core::output::OutputMode;
+    use crate::core::StatusLineGenerator;
+    use serde_json::json;
+    use std::{env, fs, process};
+
+    #[test]
+    fn rotate_shifts_and_drops_the_oldest() {
+        let dir = env::temp_dir().join(format!("ccline-test-rotate-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("records.jsonl");
+        let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+
+        fs::write(&path, "a").unwrap();
+        rotate(&path, 2).unwrap();
+        fs::write(&path, "b").unwrap();
+        rotate(&path, 2).unwrap();
+        fs::write(&path, "c").unwrap();
+        rotate(&path, 2).unwrap();
+        assert_eq!(read("records.jsonl"), None);
+        assert_eq!(read("records.jsonl.1").as_deref(), Some("c"));
+        assert_eq!(read("records.jsonl.2").as_deref(), Some("b"));
+        assert_eq!(read("records.jsonl.3"), None);
+
+        fs::write(&path, "d").unwrap();
+        rotate(&path, 0).unwrap();
+        assert_eq!(read("records.jsonl"), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stable_replay_ignores_volatile_segments() {
+        let config = Config::default();
+        let input = json!({
+            "session_id": "replay-test",
+            "model": {"id": "claude-sonnet-4-5-20250929", "display_name": "Sonnet 4.5"},
+            "workspace": {"current_dir": "/nonexistent/ccline-replay-test"},
+            "cost": {"total_cost_usd": 1.5, "total_lines_added": 3, "total_lines_removed": 1},
+        });
+        let data: InputData = serde_json::from_value(input.clone()).unwrap();
+        let generator = StatusLineGenerator::new(config.clone())
+            .with_output(OutputMode::Plain)
+            .with_alerts(false);
+        let volatile = generator.volatile_segments();
+        assert!(volatile.contains(&"cost") && volatile.contains(&"churn"));
+
+        // 录制时易变 segment 的输出与现在不同
+        let segments: Vec<RecordedSegment> = generator
+            .collect(&data)
+            .into_iter()
+            .map(|segment| RecordedSegment {
+                name: segment.name.to_string(),
+                text: if volatile.contains(&segment.name) {
+                    "elsewhere".to_string()
+                } else {
+                    segment.text
+                },
+            })
+            .collect();
+        let mut recording = Recording {
+            recorded_at: None,
+            ccline_version: None,
+            input,
+            output: Some("elsewhere".to_string()),
+            output_mode: Some(OutputMode::Ansi),
+            segments,
+        };
+        let result = replay(&[recording.clone()], &config, OutputMode::Plain, true, true);
+        assert_eq!(result.changed, 0, "{}", result.text);
+        assert_eq!(
+            replay(
+                &[recording.clone()],
+                &config,
+                OutputMode::Plain,
+                true,
+                false
+            )
+            .changed,
+            1
+        );
+
+        let model = recording
+            .segments
+            .iter_mut()
+            .find(|segment| segment.name == "model")
+            .unwrap();
+        model.text = "Other".to_string();
+        let result = replay(&[recording], &config, OutputMode::Plain, true, true);
+        assert_eq!(result.changed, 1);
+        assert!(result.text.contains("- Other"));
+    }
+}
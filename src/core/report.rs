@@ -0,0 +1,401 @@
+use crate::config::Config;
+use crate::core::format::format_token_count;
+use crate::core::history::{self, SessionRecord};
+use crate::core::models::ModelRegistry;
+use crate::core::pricing::CostEngine;
+use crate::core::transcript::{self, TokenUsage};
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// 报告的分组维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportGroup {
+    Day,
+    Week,
+    Month,
+    Project,
+    Model,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+/// 数据来源：ccline 记录的会话历史，或直接扫描 Claude Code 的 transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportSource {
+    History,
+    Transcripts,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub group: ReportGroup,
+    pub format: ReportFormat,
+    pub source: ReportSource,
+    /// 只统计该日期（含）之后的用量
+    pub since: Option<NaiveDate>,
+    /// 只统计该日期（含）之前的用量
+    pub until: Option<NaiveDate>,
+}
+
+/// 报告中的一行
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportRow {
+    pub key: String,
+    pub sessions: usize,
+    #[serde(flatten)]
+    pub usage: TokenUsage,
+    pub total_tokens: u64,
+    pub cost_usd: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub group: ReportGroup,
+    pub rows: Vec<ReportRow>,
+    pub total: ReportRow,
+}
+
+/// 分组前的最小统计单元：一个会话中某个模型的用量
+struct UsageItem {
+    time: Option<DateTime<Local>>,
+    session: String,
+    project: String,
+    model: String,
+    usage: TokenUsage,
+    cost: f64,
+}
+
+/// 按选项汇总用量并格式化为文本
+pub fn generate(config: &Config, options: &ReportOptions) -> Result<String, serde_json::Error> {
+    let report = build(config, options);
+    match options.format {
+        ReportFormat::Table => Ok(format_table(&report)),
+        ReportFormat::Csv => Ok(format_csv(&report)),
+        ReportFormat::Json => serde_json::to_string_pretty(&report),
+    }
+}
+
+pub fn build(config: &Config, options: &ReportOptions) -> Report {
+    let registry = ModelRegistry::new(&config.models);
+    let items = match options.source {
+        ReportSource::History => history_items(config),
+        ReportSource::Transcripts => transcript_items(config, &registry),
+    };
+
+    let mut groups: BTreeMap<String, (ReportRow, BTreeSet<String>)> = BTreeMap::new();
+    let mut all_sessions = BTreeSet::new();
+    let mut total = ReportRow {
+        key: "Total".to_string(),
+        ..Default::default()
+    };
+
+    for item in items {
+        let date = item.time.map(|time| time.date_naive());
+        let in_range = |check: Option<NaiveDate>, keep: fn(NaiveDate, NaiveDate) -> bool| {
+            check.is_none_or(|bound| date.is_some_and(|date| keep(date, bound)))
+        };
+        if !in_range(options.since, |date, since| date >= since)
+            || !in_range(options.until, |date, until| date <= until)
+        {
+            continue;
+        }
+
+        let key = match options.group {
+            ReportGroup::Day => format_time(item.time, "%Y-%m-%d"),
+            ReportGroup::Week => format_time(item.time, "%G-W%V"),
+            ReportGroup::Month => format_time(item.time, "%Y-%m"),
+            ReportGroup::Project => item.project.clone(),
+            ReportGroup::Model => registry.resolve(&[&item.model]).name,
+        };
+
+        let (row, sessions) = groups.entry(key.clone()).or_default();
+        row.key = key;
+        row.usage.add(&item.usage);
+        row.cost_usd += item.cost;
+        sessions.insert(item.session.clone());
+
+        total.usage.add(&item.usage);
+        total.cost_usd += item.cost;
+        all_sessions.insert(item.session);
+    }
+
+    let mut rows: Vec<ReportRow> = groups
+        .into_values()
+        .map(|(mut row, sessions)| {
+            row.sessions = sessions.len();
+            row.total_tokens = row.usage.total();
+            row
+        })
+        .collect();
+    // 时间分组按时间排序，其余按费用从高到低排序
+    if matches!(options.group, ReportGroup::Project | ReportGroup::Model) {
+        rows.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+    }
+
+    total.sessions = all_sessions.len();
+    total.total_tokens = total.usage.total();
+    Report {
+        group: options.group,
+        rows,
+        total,
+    }
+}
+
+fn format_time(time: Option<DateTime<Local>>, format: &str) -> String {
+    time.map_or_else(|| "unknown".to_string(), |t| t.format(format).to_string())
+}
+
+fn parse_timestamp(timestamp: Option<&str>) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|time| time.with_timezone(&Local))
+}
+
+/// 从会话历史生成统计单元，会话费用按各模型计算费用的比例分摊。
+/// 历史只记录会话的累计用量，跨天的会话整体计入第一条消息所在的日期（周、月同理），
+/// 需要按消息时间拆分时使用 transcript 数据源
+fn history_items(config: &Config) -> Vec<UsageItem> {
+    let Some(path) = history::history_path(config) else {
+        return Vec::new();
+    };
+
+    let mut items = Vec::new();
+    for record in history::read_records(path) {
+        let time = parse_timestamp(record.started_at.as_deref())
+            .or_else(|| Local.timestamp_opt(record.recorded_at as i64, 0).single());
+        let SessionRecord {
+            session_id,
+            project,
+            model,
+            cost_usd,
+            usage,
+            models,
+            ..
+        } = record;
+
+        if models.is_empty() {
+            items.push(UsageItem {
+                time,
+                session: session_id,
+                project,
+                model,
+                usage,
+                cost: cost_usd,
+            });
+            continue;
+        }
+
+        let computed: f64 = models.values().map(|m| m.cost_usd).sum();
+        let count = models.len() as f64;
+        for (model, model_usage) in models {
+            let share = if computed > 0.0 {
+                model_usage.cost_usd / computed
+            } else {
+                1.0 / count
+            };
+            items.push(UsageItem {
+                time,
+                session: session_id.clone(),
+                project: project.clone(),
+                model,
+                usage: model_usage.usage,
+                cost: cost_usd * share,
+            });
+        }
+    }
+    items
+}
+
+/// 扫描 Claude Code 的 transcript，每条消息为一个统计单元，跨文件按消息去重
+fn transcript_items(config: &Config, registry: &ModelRegistry) -> Vec<UsageItem> {
+    let engine = CostEngine::from_config(registry.clone(), &config.cost);
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+
+    for file in transcript::transcript_files() {
+        let session = file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // 项目目录名是 Claude Code 对工作目录的编码，如 -home-user-project
+        let project = file
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        for entry in transcript::read_usage_entries(&file) {
+            if let Some(key) = &entry.key {
+                if !seen.insert(key.clone()) {
+                    continue;
+                }
+            }
+            items.push(UsageItem {
+                time: parse_timestamp(entry.timestamp.as_deref()),
+                session: session.clone(),
+                project: project.clone(),
+                cost: engine.entry_cost(&entry).map_or(0.0, |c| c.total()),
+                model: entry.model,
+                usage: entry.usage,
+            });
+        }
+    }
+    items
+}
+
+fn group_label(group: ReportGroup) -> &'static str {
+    match group {
+        ReportGroup::Day => "Day",
+        ReportGroup::Week => "Week",
+        ReportGroup::Month => "Month",
+        ReportGroup::Project => "Project",
+        ReportGroup::Model => "Model",
+    }
+}
+
+fn format_table(report: &Report) -> String {
+    let header = [
+        group_label(report.group),
+        "Sessions",
+        "Input",
+        "Output",
+        "Cache W",
+        "Cache R",
+        "Total",
+        "Cost",
+    ];
+    let cells = |row: &ReportRow| {
+        vec![
+            row.key.clone(),
+            row.sessions.to_string(),
+            format_token_count(row.usage.input_tokens),
+            format_token_count(row.usage.output_tokens),
+            format_token_count(row.usage.cache_creation_input_tokens),
+            format_token_count(row.usage.cache_read_input_tokens),
+            format_token_count(row.total_tokens),
+            format!("${:.2}", row.cost_usd),
+        ]
+    };
+
+    let mut lines: Vec<Vec<String>> = vec![header.iter().map(|h| h.to_string()).collect()];
+    lines.extend(report.rows.iter().map(cells));
+    lines.push(cells(&report.total));
+
+    let mut widths = vec![0; header.len()];
+    for line in &lines {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let render = |line: &[String]| {
+        line.iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, width))| {
+                // 第一列左对齐，数字列右对齐
+                if i == 0 {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+    let separator = widths
+        .iter()
+        .map(|width| "-".repeat(*width))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    let last = lines.len() - 1;
+    let mut output = Vec::with_capacity(lines.len() + 2);
+    output.push(render(&lines[0]));
+    output.push(separator.clone());
+    output.extend(lines[1..last].iter().map(|line| render(line)));
+    output.push(separator);
+    output.push(render(&lines[last]));
+    output.join("\n")
+}
+
+fn format_csv(report: &Report) -> String {
+    let mut output = format!(
+        "{},sessions,input_tokens,output_tokens,cache_creation_input_tokens,cache_read_input_tokens,total_tokens,cost_usd\n",
+        group_label(report.group).to_lowercase()
+    );
+    // 最后一行为合计，与表格输出一致
+    for row in report.rows.iter().chain([&report.total]) {
+        output.push_str(&format!(
+            "{},{},{},{},{},{},{},{:.4}\n",
+            csv_field(&row.key),
+            row.sessions,
+            row.usage.input_tokens,
+            row.usage.output_tokens,
+            row.usage.cache_creation_input_tokens,
+            row.usage.cache_read_input_tokens,
+            row.total_tokens,
+            row.cost_usd
+        ));
+    }
+    output.trim_end().to_string()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_field, format_csv, Report, ReportGroup, ReportRow};
+    use crate::core::transcript::TokenUsage;
+
+    fn row(key: &str, sessions: usize, input: u64, cost: f64) -> ReportRow {
+        ReportRow {
+            key: key.to_string(),
+            sessions,
+            usage: TokenUsage {
+                input_tokens: input,
+                ..Default::default()
+            },
+            total_tokens: input,
+            cost_usd: cost,
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("cr\r"), "\"cr\r\"");
+    }
+
+    #[test]
+    fn csv_ends_with_the_total_row() {
+        let report = Report {
+            group: ReportGroup::Project,
+            rows: vec![row("/w/a,b", 2, 100, 1.5), row("/w/c", 1, 50, 0.25)],
+            total: row("Total", 3, 150, 1.75),
+        };
+        let csv = format_csv(&report);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("project,sessions,"));
+        assert_eq!(lines[1], "\"/w/a,b\",2,100,0,0,0,100,1.5000");
+        assert_eq!(lines[3], "Total,3,150,0,0,0,150,1.7500");
+    }
+}
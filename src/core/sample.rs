@@ -0,0 +1,191 @@
+use crate::config::InputData;
+use clap::ValueEnum;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 内置示例输入，用于诊断和预览，不依赖 Claude Code
+const SAMPLE_INPUT: &str = r#"{
+    "session_id": "ccline-sample",
+    "model": { "id": "claude-sonnet-4-5-20250929", "display_name": "Sonnet 4.5" },
+    "version": "2.0.0",
+    "cost": {
+        "total_cost_usd": 1.84,
+        "total_duration_ms": 2700000,
+        "total_api_duration_ms": 960000,
+        "total_lines_added": 256,
+        "total_lines_removed": 31
+    },
+    "context_window": {
+        "context_window_size": 200000,
+        "current_usage": {
+            "input_tokens": 2400,
+            "output_tokens": 1800,
+            "cache_creation_input_tokens": 6100,
+            "cache_read_input_tokens": 96300
+        }
+    }
+}"#;
+
+/// 示例输入，工作目录为当前目录
+pub fn sample_input() -> InputData {
+    let mut input: InputData = serde_json::from_str(SAMPLE_INPUT).unwrap_or_default();
+    let cwd = env::current_dir()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .unwrap_or_else(|_| ".".to_string());
+    input.workspace.current_dir = cwd.clone();
+    input.cwd = Some(cwd);
+    input
+}
+
+/// 预览用的典型场景
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Scenario {
+    /// Git repository with no changes
+    Clean,
+    /// Modified and untracked files
+    Dirty,
+    /// Merge in progress with conflicts
+    Conflicts,
+    /// Context window 95% full
+    #[value(name = "context-95")]
+    Context95,
+    /// Long Opus session with high cost
+    HighCost,
+}
+
+/// 场景输入，git 场景的临时仓库在 drop 时删除
+pub struct ScenarioInput {
+    pub input: InputData,
+    repo: Option<PathBuf>,
+}
+
+impl Drop for ScenarioInput {
+    fn drop(&mut self) {
+        if let Some(repo) = &self.repo {
+            let _ = fs::remove_dir_all(repo);
+        }
+    }
+}
+
+/// 构造场景输入；git 场景会在临时目录中创建对应状态的仓库
+pub fn scenario_input(scenario: Scenario) -> io::Result<ScenarioInput> {
+    let mut input = sample_input();
+
+    match scenario {
+        Scenario::Clean | Scenario::Dirty | Scenario::Conflicts => {
+            let name = scenario
+                .to_possible_value()
+                .map_or_else(String::new, |v| v.get_name().to_string());
+            let dir =
+                env::temp_dir().join(format!("ccline-preview-{}-{}", name, std::process::id()));
+            let cwd = dir.to_string_lossy().to_string();
+            input.workspace.current_dir = cwd.clone();
+            input.cwd = Some(cwd);
+            // 先构造返回值，创建仓库失败时也会清理临时目录
+            let scenario_input = ScenarioInput {
+                input,
+                repo: Some(dir.clone()),
+            };
+            create_repo(&dir, scenario)?;
+            return Ok(scenario_input);
+        }
+        Scenario::Context95 => {
+            if let Some(usage) = input
+                .context_window
+                .as_mut()
+                .and_then(|c| c.current_usage.as_mut())
+            {
+                usage.cache_read_input_tokens = 181_500;
+            }
+        }
+        Scenario::HighCost => {
+            input.model.id = Some("claude-opus-4-1-20250805".to_string());
+            input.model.display_name = "Opus 4.1".to_string();
+            if let Some(cost) = input.cost.as_mut() {
+                cost.total_cost_usd = Some(48.27);
+                cost.total_duration_ms = Some(19_800_000);
+                cost.total_api_duration_ms = Some(7_400_000);
+                cost.total_lines_added = Some(3_412);
+                cost.total_lines_removed = Some(1_207);
+            }
+        }
+    }
+
+    Ok(ScenarioInput { input, repo: None })
+}
+
+fn create_repo(dir: &Path, scenario: Scenario) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    // --initial-branch 需要 git 2.28+，改用 symbolic-ref 兼容旧版本
+    git(dir, &["init", "--quiet"])?;
+    git(dir, &["symbolic-ref", "HEAD", "refs/heads/main"])?;
+    fs::write(dir.join("README.md"), "# preview\n")?;
+    fs::write(dir.join("main.rs"), "fn main() {}\n")?;
+    git(dir, &["add", "."])?;
+    git(dir, &["commit", "--quiet", "-m", "initial"])?;
+
+    match scenario {
+        Scenario::Dirty => {
+            fs::write(
+                dir.join("main.rs"),
+                "fn main() {\n    println!(\"hi\");\n}\n",
+            )?;
+            fs::write(dir.join("notes.txt"), "todo\n")?;
+        }
+        Scenario::Conflicts => {
+            // 两个分支修改同一行后合并
+            git(dir, &["checkout", "--quiet", "-b", "feature"])?;
+            fs::write(dir.join("main.rs"), "fn main() { feature(); }\n")?;
+            git(dir, &["commit", "--quiet", "-am", "feature"])?;
+            git(dir, &["checkout", "--quiet", "main"])?;
+            fs::write(dir.join("main.rs"), "fn main() { fix(); }\n")?;
+            git(dir, &["commit", "--quiet", "-am", "fix"])?;
+            // 合并冲突时 git 以非零状态退出，这是预期的结果；
+            // 但如果没有留下冲突文件，说明是别的原因失败，需要上报
+            if let Err(err) = git(dir, &["merge", "--quiet", "feature"]) {
+                if !has_conflicts(dir)? {
+                    return Err(err);
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 仓库中是否存在未解决的冲突文件
+fn has_conflicts(dir: &Path) -> io::Result<bool> {
+    let output = Command::new("git")
+        .args(["diff", "--name-only", "--diff-filter=U"])
+        .current_dir(dir)
+        .output()?;
+    Ok(output.status.success() && !output.stdout.is_empty())
+}
+
+/// 在临时仓库中执行 git，使用固定的身份，不依赖用户的 git 配置
+fn git(dir: &Path, args: &[&str]) -> io::Result<()> {
+    let output = Command::new("git")
+        .args([
+            "-c",
+            "user.name=ccline",
+            "-c",
+            "user.email=ccline@localhost",
+            "-c",
+            "commit.gpgsign=false",
+        ])
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
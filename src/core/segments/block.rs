@@ -0,0 +1,126 @@
+use super::Segment;
+use crate::config::{BlockConfig, InputData};
+use crate::core::blocks::{self, BLOCK_SECS};
+use crate::core::format::{format_duration, format_token_count};
+use crate::core::index::SharedEntries;
+use crate::core::models::ModelRegistry;
+use crate::core::output::{Color, Span, Style};
+use crate::core::pricing::CostEngine;
+use chrono::{Local, TimeZone, Utc};
+
+/// 5 小时计费块 segment，显示距重置的剩余时间、块内用量和按当前速率推算的块结束时用量
+pub struct BlockSegment {
+    enabled: bool,
+    engine: CostEngine,
+    config: BlockConfig,
+    usage: SharedEntries,
+}
+
+impl BlockSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            engine: CostEngine::new(ModelRegistry::default(), 1.0),
+            config: BlockConfig::default(),
+            usage: SharedEntries::default(),
+        }
+    }
+
+    pub fn with_engine(mut self, engine: CostEngine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    pub fn with_config(mut self, config: BlockConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 与其他 segment 共享的用量索引
+    pub fn with_usage(mut self, usage: SharedEntries) -> Self {
+        self.usage = usage;
+        self
+    }
+}
+
+impl Segment for BlockSegment {
+    fn name(&self) -> &'static str {
+        "block"
+    }
+
+    fn render(&self, _input: &InputData) -> Vec<Span> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let now = Utc::now().timestamp();
+        let Some(block) = blocks::active_block(&self.usage, now, &self.engine) else {
+            return Vec::new();
+        };
+
+        let remaining = block.end() - now;
+        // 块开始后不足一分钟时速率没有意义，按一分钟计算
+        let elapsed = (now - block.start).max(60);
+        let scale = BLOCK_SECS as f64 / elapsed as f64;
+        let tokens = block.usage.total();
+        let projected_tokens = (tokens as f64 * scale) as u64;
+        let projected_cost = block.cost * scale;
+
+        // 按当前速率会在块结束前超出限额时提示
+        let over_limit = self
+            .config
+            .token_limit
+            .is_some_and(|limit| projected_tokens > limit)
+            || self
+                .config
+                .cost_limit
+                .is_some_and(|limit| projected_cost > limit);
+
+        let mut spans = Vec::new();
+        if over_limit {
+            spans.push(Span::styled("⚠ ", Style::fg(Color::Red).bold()));
+        }
+
+        let resets = Local
+            .timestamp_opt(block.end(), 0)
+            .single()
+            .map(|time| format!(" (resets {})", time.format("%H:%M")))
+            .unwrap_or_default();
+        spans.push(Span::styled(
+            format!(
+                "⏳ {} left{}",
+                format_duration(remaining as u64 * 1000),
+                resets
+            ),
+            Style::fg(Color::Blue),
+        ));
+        spans.push(Span::styled(
+            format!(" · ${:.2} · {} tok", block.cost, format_token_count(tokens)),
+            Style::fg(Color::BrightBlack),
+        ));
+
+        let projection_color = if over_limit {
+            Color::Red
+        } else {
+            Color::BrightBlack
+        };
+        spans.push(Span::styled(
+            format!(
+                " →${:.2} {} tok",
+                projected_cost,
+                format_token_count(projected_tokens)
+            ),
+            Style::fg(projection_color),
+        ));
+
+        spans
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn volatile(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,103 @@
+use super::Segment;
+use crate::config::{BudgetSource, Config, InputData};
+use crate::core::budget::{BudgetLevel, SharedStatuses};
+use crate::core::index::SharedEntries;
+use crate::core::output::{Color, Span, Style};
+use crate::core::quota;
+
+/// 预算 segment，显示各周期的已用金额和预算，接近或超出预算时变色
+pub struct BudgetSegment {
+    enabled: bool,
+    statuses: SharedStatuses,
+    hyperlinks: bool,
+}
+
+impl BudgetSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            statuses: SharedStatuses::default(),
+            hyperlinks: false,
+        }
+    }
+
+    /// 预算需要模型价格、当天费用和用量索引的配置，因此传入完整配置
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.statuses = SharedStatuses::new(config, SharedEntries::default());
+        self
+    }
+
+    /// 与整行的警告色共用的预算状态，告警命令由 StatusLineGenerator 执行
+    pub fn with_statuses(mut self, statuses: SharedStatuses) -> Self {
+        self.statuses = statuses;
+        self
+    }
+
+    /// 使用 PackyCode 额度时链接到控制台
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+}
+
+impl Segment for BudgetSegment {
+    fn name(&self) -> &'static str {
+        "budget"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let statuses = self.statuses.get(input);
+        if statuses.is_empty() {
+            return Vec::new();
+        }
+
+        let link = (self.hyperlinks
+            && self.statuses.config().budget.source == BudgetSource::Packycode)
+            .then(|| quota::DASHBOARD_URL.to_string());
+        let mut spans = Vec::new();
+        let worst = statuses.iter().map(|s| s.level).max();
+        let icon = if worst == Some(BudgetLevel::Critical) {
+            Span::styled("⚠ ", Style::fg(Color::Red).bold())
+        } else {
+            Span::plain("💳 ")
+        };
+        spans.push(icon);
+
+        for (i, status) in statuses.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(" · ", Style::fg(Color::BrightBlack)));
+            }
+            let style = match status.level {
+                BudgetLevel::Normal => Style::fg(Color::Green),
+                BudgetLevel::Warning => Style::fg(Color::Yellow).bold(),
+                BudgetLevel::Critical => Style::fg(Color::Red).bold(),
+            };
+            spans.push(
+                Span::styled(
+                    format!(
+                        "{} ${:.2}/${:.0}",
+                        status.period.label(),
+                        status.spent,
+                        status.limit
+                    ),
+                    style,
+                )
+                .with_link(link.clone()),
+            );
+        }
+
+        spans
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn volatile(&self) -> bool {
+        true
+    }
+}
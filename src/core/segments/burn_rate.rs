@@ -0,0 +1,122 @@
+use super::text_mode::{get_icons, Icons, UNICODE_ICONS};
+use super::Segment;
+use crate::config::{BurnRateConfig, IconMode, InputData};
+use crate::core::format::format_token_count;
+use crate::core::output::{Color, Span, Style};
+use crate::core::transcript::{self, TokenUsage};
+
+/// 会话太短时速率波动太大，不显示
+const MIN_DURATION_MS: u64 = 60_000;
+
+/// 消耗速率 segment，显示每小时费用、每分钟 token 数和按当前速率推算的会话费用
+pub struct BurnRateSegment {
+    enabled: bool,
+    config: BurnRateConfig,
+    icons: Icons,
+}
+
+impl BurnRateSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            config: BurnRateConfig::default(),
+            icons: UNICODE_ICONS,
+        }
+    }
+
+    pub fn with_config(mut self, config: BurnRateConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_icons(mut self, mode: IconMode) -> Self {
+        self.icons = get_icons(mode);
+        self
+    }
+
+    fn rate_style(&self, cost_per_hour: f64) -> Style {
+        let exceeds = |limit: Option<f64>| limit.is_some_and(|limit| cost_per_hour >= limit);
+        if exceeds(self.config.critical_cost_per_hour) {
+            Style::fg(Color::Red).bold()
+        } else if exceeds(self.config.warn_cost_per_hour) {
+            Style::fg(Color::Yellow).bold()
+        } else {
+            Style::fg(Color::Magenta)
+        }
+    }
+}
+
+impl Segment for BurnRateSegment {
+    fn name(&self) -> &'static str {
+        "burn_rate"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let cost = match &input.cost {
+            Some(c) => c,
+            None => return Vec::new(),
+        };
+        let duration_ms = match cost.total_duration_ms {
+            Some(ms) if ms >= MIN_DURATION_MS => ms,
+            _ => return Vec::new(),
+        };
+        let hours = duration_ms as f64 / 3_600_000.0;
+
+        let mut spans = Vec::new();
+
+        if let Some(usd) = cost.total_cost_usd {
+            let cost_per_hour = usd / hours;
+            let style = self.rate_style(cost_per_hour);
+            if !self.icons.burn_rate.is_empty() {
+                spans.push(Span::styled(format!("{} ", self.icons.burn_rate), style));
+            }
+            spans.push(Span::styled(format!("${:.2}/h", cost_per_hour), style));
+
+            // 按当前速率推算到下一个 projection_hours 整数倍时的会话总费用，
+            // 会话超过一个窗口后推算下一个窗口结束时的费用，而不是停在当前费用
+            if self.config.projection_hours > 0.0 {
+                let window = self.config.projection_hours;
+                let projected = cost_per_hour * (hours / window).ceil().max(1.0) * window;
+                spans.push(Span::styled(
+                    format!(" →${:.2}", projected),
+                    Style::fg(Color::BrightBlack),
+                ));
+            }
+        }
+
+        // 每分钟 token 数只统计实际处理的 token（输入、输出、缓存写入），不含缓存读取
+        if let Some(path) = input.transcript_path.as_deref() {
+            let entries = transcript::session_entries(path);
+            let mut usage = TokenUsage::default();
+            for entry in entries.iter() {
+                usage.add(&entry.usage);
+            }
+            let fresh =
+                usage.input_tokens + usage.output_tokens + usage.cache_creation_input_tokens;
+            if fresh > 0 {
+                let per_minute = (fresh as f64 / (hours * 60.0)).round() as u64;
+                if !spans.is_empty() {
+                    spans.push(Span::plain(" "));
+                }
+                spans.push(Span::styled(
+                    format!("{} tok/min", format_token_count(per_minute)),
+                    Style::fg(Color::Magenta),
+                ));
+            }
+        }
+
+        spans
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn volatile(&self) -> bool {
+        true
+    }
+}
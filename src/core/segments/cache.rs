@@ -0,0 +1,114 @@
+use super::Segment;
+use crate::config::InputData;
+use crate::core::models::ModelRegistry;
+use crate::core::output::{Color, Span, Style};
+use crate::core::pricing::CostEngine;
+use crate::core::transcript::{self, TokenUsage};
+
+/// 提示缓存 segment，显示上一轮和整个会话的缓存命中率以及缓存节省的费用
+pub struct CacheSegment {
+    enabled: bool,
+    engine: CostEngine,
+}
+
+impl CacheSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            engine: CostEngine::new(ModelRegistry::default(), 1.0),
+        }
+    }
+
+    pub fn with_engine(mut self, engine: CostEngine) -> Self {
+        self.engine = engine;
+        self
+    }
+}
+
+/// 缓存命中率：缓存读取占全部输入 token 的比例
+fn hit_ratio(usage: &TokenUsage) -> Option<f64> {
+    let prompt =
+        usage.input_tokens + usage.cache_read_input_tokens + usage.cache_creation_input_tokens;
+    (prompt > 0).then(|| usage.cache_read_input_tokens as f64 / prompt as f64 * 100.0)
+}
+
+impl Segment for CacheSegment {
+    fn name(&self) -> &'static str {
+        "cache"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let entries = input
+            .transcript_path
+            .as_deref()
+            .map(transcript::session_entries)
+            .unwrap_or_default();
+
+        // 上一轮用量优先取 Claude Code 传入的 current_usage，否则取 transcript 最后一条
+        let last_turn = input
+            .context_window
+            .as_ref()
+            .and_then(|cw| cw.current_usage.as_ref())
+            .map(|usage| TokenUsage {
+                input_tokens: usage.input_tokens.into(),
+                output_tokens: usage.output_tokens.into(),
+                cache_creation_input_tokens: usage.cache_creation_input_tokens.into(),
+                cache_read_input_tokens: usage.cache_read_input_tokens.into(),
+            })
+            .or_else(|| entries.last().map(|entry| entry.usage));
+        let Some(last_turn) = last_turn else {
+            return Vec::new();
+        };
+        let Some(last_ratio) = hit_ratio(&last_turn) else {
+            return Vec::new();
+        };
+
+        let mut session = TokenUsage::default();
+        for entry in entries.iter() {
+            session.add(&entry.usage);
+        }
+
+        let mut spans = Vec::new();
+
+        // 之前有缓存读取而上一轮完全没有，说明缓存失效了
+        let invalidated =
+            last_turn.cache_read_input_tokens == 0 && session.cache_read_input_tokens > 0;
+        if invalidated {
+            spans.push(Span::styled("⚠ cache miss ", Style::fg(Color::Red).bold()));
+        }
+
+        spans.push(Span::styled(
+            format!("⚡{:.0}%", last_ratio),
+            Style::fg(Color::BrightCyan),
+        ));
+
+        if let Some(session_ratio) = hit_ratio(&session) {
+            spans.push(Span::styled(
+                format!(" · session {:.0}%", session_ratio),
+                Style::fg(Color::BrightBlack),
+            ));
+        }
+
+        let saved = self.engine.cache_savings(&entries);
+        if saved.abs() >= 0.005 {
+            spans.push(Span::styled(
+                format!(" · saved ${:.2}", saved),
+                Style::fg(Color::Green),
+            ));
+        }
+
+        spans
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn volatile(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,129 @@
+use super::Segment;
+use crate::config::{ChurnConfig, InputData};
+use crate::core::output::{Color, Span, Style};
+use std::process::Command;
+
+/// 代码变更 segment，显示会话中增删的代码行数
+pub struct ChurnSegment {
+    enabled: bool,
+    config: ChurnConfig,
+}
+
+impl ChurnSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            config: ChurnConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: ChurnConfig) -> Self {
+        self.config = config;
+        self
+    }
+}
+
+/// 工作区相对 HEAD 的增删行数，解析 `git diff --shortstat HEAD` 的输出
+fn git_diff_stat(working_dir: &str) -> Option<(u32, u32)> {
+    let output = Command::new("git")
+        .args(["diff", "--shortstat", "HEAD"])
+        .current_dir(working_dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // 形如 " 3 files changed, 10 insertions(+), 2 deletions(-)"，没有变更时输出为空
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let mut insertions = 0;
+    let mut deletions = 0;
+    for part in stdout.trim().split(", ") {
+        let Some((count, kind)) = part.split_once(' ') else {
+            continue;
+        };
+        let Ok(count) = count.parse::<u32>() else {
+            continue;
+        };
+        if kind.starts_with("insertion") {
+            insertions = count;
+        } else if kind.starts_with("deletion") {
+            deletions = count;
+        }
+    }
+    Some((insertions, deletions))
+}
+
+impl Segment for ChurnSegment {
+    fn name(&self) -> &'static str {
+        "churn"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let mut spans = Vec::new();
+
+        // 会话中的代码行数变更（绿色+数 / 红色-数）
+        if let Some(cost) = &input.cost {
+            let lines_added = cost.total_lines_added.unwrap_or(0);
+            let lines_removed = cost.total_lines_removed.unwrap_or(0);
+            let changed = lines_added + lines_removed;
+            if changed > 0 {
+                spans.push(Span::styled(
+                    format!("+{}", lines_added),
+                    Style::fg(Color::Green),
+                ));
+                spans.push(Span::plain("/"));
+                spans.push(Span::styled(
+                    format!("-{}", lines_removed),
+                    Style::fg(Color::Red),
+                ));
+
+                if self.config.net {
+                    let net = i64::from(lines_added) - i64::from(lines_removed);
+                    spans.push(Span::styled(
+                        format!(" net {:+}", net),
+                        Style::fg(Color::BrightBlack),
+                    ));
+                }
+
+                if self.config.cost_per_100_lines {
+                    if let Some(usd) = cost.total_cost_usd {
+                        spans.push(Span::styled(
+                            format!(" ${:.2}/100L", usd / f64::from(changed) * 100.0),
+                            Style::fg(Color::Yellow),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // 与工作区的实际 diff 对比（包含会话之外的改动）
+        if self.config.git_diff {
+            if let Some((insertions, deletions)) = git_diff_stat(input.current_dir()) {
+                if insertions > 0 || deletions > 0 {
+                    if !spans.is_empty() {
+                        spans.push(Span::plain(" "));
+                    }
+                    spans.push(Span::styled(
+                        format!("git +{}/-{}", insertions, deletions),
+                        Style::fg(Color::BrightBlack),
+                    ));
+                }
+            }
+        }
+
+        spans
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn volatile(&self) -> bool {
+        true
+    }
+}
@@ -1,49 +1,148 @@
+use super::text_mode::{get_icons, Icons, UNICODE_ICONS};
 use super::Segment;
-use crate::config::InputData;
+use crate::config::{IconMode, InputData};
+use crate::core::models::ModelRegistry;
+use crate::core::output::{Color, Span, Style};
+use crate::core::pricing::{CostEngine, SessionCost};
+use crate::core::transcript;
 
 /// 会话费用 segment，显示当前会话的总费用
 pub struct CostSegment {
     enabled: bool,
+    engine: CostEngine,
+    breakdown: bool,
+    verify: bool,
+    icons: Icons,
 }
 
 impl CostSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            engine: CostEngine::new(ModelRegistry::default(), 1.0),
+            breakdown: false,
+            verify: false,
+            icons: UNICODE_ICONS,
+        }
+    }
+
+    pub fn with_engine(mut self, engine: CostEngine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    pub fn with_breakdown(mut self, breakdown: bool) -> Self {
+        self.breakdown = breakdown;
+        self
+    }
+
+    pub fn with_verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    pub fn with_icons(mut self, mode: IconMode) -> Self {
+        self.icons = get_icons(mode);
+        self
+    }
+
+    /// 根据 transcript 中每条消息的用量计算会话费用
+    fn compute_session_cost(&self, input: &InputData) -> Option<SessionCost> {
+        let path = input.transcript_path.as_deref()?;
+        let entries = transcript::session_entries(path);
+        if entries.is_empty() {
+            return None;
+        }
+        Some(self.engine.session_cost(&entries))
     }
 }
 
 impl Segment for CostSegment {
-    fn render(&self, input: &InputData) -> String {
+    fn name(&self) -> &'static str {
+        "cost"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
         if !self.enabled {
-            return String::new();
+            return Vec::new();
         }
 
         let cost = match &input.cost {
             Some(c) => c,
-            None => return String::new(),
+            None => return Vec::new(),
+        };
+
+        let reported = cost.total_cost_usd;
+        // 只有需要时才解析 transcript
+        let computed = if self.breakdown || self.verify || reported.is_none() {
+            self.compute_session_cost(input)
+        } else {
+            None
         };
 
-        let mut parts = Vec::new();
+        let mut spans = Vec::new();
 
-        // 费用
-        if let Some(usd) = cost.total_cost_usd {
-            parts.push(format!("${:.2}", usd));
+        // 费用，Claude Code 未报告时使用计算值
+        let total = reported.or_else(|| computed.as_ref().map(|c| c.total.total()));
+        if let Some(usd) = total {
+            let style = Style::fg(Color::Yellow).bold();
+            if !self.icons.cost.is_empty() {
+                spans.push(Span::styled(format!("{} ", self.icons.cost), style));
+            }
+            spans.push(Span::styled(format!("${:.2}", usd), style));
         }
 
-        // 代码行数变更（绿色+数 / 红色-数）
-        let lines_added = cost.total_lines_added.unwrap_or(0);
-        let lines_removed = cost.total_lines_removed.unwrap_or(0);
-        if lines_added > 0 || lines_removed > 0 {
-            parts.push(format!(
-                "\x1b[32m+{}\x1b[0m/\x1b[31m-{}\x1b[0m",
-                lines_added, lines_removed
-            ));
+        if let Some(session) = &computed {
+            let breakdown = &session.total;
+            if self.breakdown {
+                spans.push(Span::styled(
+                    format!(
+                        " (in ${:.2} · out ${:.2} · cw ${:.2} · cr ${:.2})",
+                        breakdown.input,
+                        breakdown.output,
+                        breakdown.cache_write,
+                        breakdown.cache_read
+                    ),
+                    Style::fg(Color::BrightBlack),
+                ));
+
+                // 会话中切换过模型时再按模型拆分
+                if session.by_model.len() > 1 {
+                    let models: Vec<String> = session
+                        .by_model
+                        .iter()
+                        .map(|(model, cost)| format!("{} ${:.2}", model, cost.total()))
+                        .collect();
+                    spans.push(Span::styled(
+                        format!(" [{}]", models.join(" · ")),
+                        Style::fg(Color::BrightBlack),
+                    ));
+                }
+            }
+
+            // 计算值与报告值相差超过 5% 时以黄色提示
+            if self.verify {
+                if let Some(reported) = reported {
+                    let calculated = breakdown.total();
+                    let matches = reported == 0.0 && calculated == 0.0
+                        || (calculated - reported).abs() / reported.max(calculated) <= 0.05;
+                    let color = if matches { Color::Green } else { Color::Yellow };
+                    spans.push(Span::styled(
+                        format!(" calc ${:.2}", calculated),
+                        Style::fg(color),
+                    ));
+                }
+            }
         }
 
-        parts.join(" ")
+        spans
     }
 
     fn enabled(&self) -> bool {
         self.enabled
     }
+
+    fn volatile(&self) -> bool {
+        true
+    }
 }
@@ -0,0 +1,68 @@
+use super::Segment;
+use crate::config::{DailyConfig, InputData};
+use crate::core::daily;
+use crate::core::index::SharedEntries;
+use crate::core::models::ModelRegistry;
+use crate::core::output::{Color, Span, Style};
+use crate::core::pricing::CostEngine;
+
+/// 当天费用 segment，汇总当天所有会话的费用，不依赖服务商 API
+pub struct DailySegment {
+    enabled: bool,
+    engine: CostEngine,
+    config: DailyConfig,
+    usage: SharedEntries,
+}
+
+impl DailySegment {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            engine: CostEngine::new(ModelRegistry::default(), 1.0),
+            config: DailyConfig::default(),
+            usage: SharedEntries::default(),
+        }
+    }
+
+    pub fn with_engine(mut self, engine: CostEngine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    pub fn with_config(mut self, config: DailyConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 与其他 segment 共享的用量索引
+    pub fn with_usage(mut self, usage: SharedEntries) -> Self {
+        self.usage = usage;
+        self
+    }
+}
+
+impl Segment for DailySegment {
+    fn name(&self) -> &'static str {
+        "daily"
+    }
+
+    fn render(&self, _input: &InputData) -> Vec<Span> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let spend = daily::today_spend(&self.usage, &self.engine, &self.config);
+        vec![Span::styled(
+            format!("📅 Today ${:.2}", spend),
+            Style::fg(Color::Cyan),
+        )]
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn volatile(&self) -> bool {
+        true
+    }
+}
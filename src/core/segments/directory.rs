@@ -1,36 +1,294 @@
+use super::text_mode::{get_icons, Icons, UNICODE_ICONS};
 use super::Segment;
-use crate::config::InputData;
-use std::path::Path;
+use crate::config::{DirectoryStyle, IconMode, InputData};
+use crate::core::output::{file_url, Color, Span, Style};
+use crate::core::project::{self, ProjectInfo};
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
 
 pub struct DirectorySegment {
     enabled: bool,
+    hyperlinks: bool,
+    style: DirectoryStyle,
+    max_depth: usize,
+    project: bool,
+    icon_mode: IconMode,
+    icons: Icons,
 }
 
 impl DirectorySegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            hyperlinks: false,
+            style: DirectoryStyle::Name,
+            max_depth: 0,
+            project: false,
+            icon_mode: IconMode::Unicode,
+            icons: UNICODE_ICONS,
+        }
+    }
+
+    pub fn with_hyperlinks(mut self, hyperlinks: bool) -> Self {
+        self.hyperlinks = hyperlinks;
+        self
+    }
+
+    pub fn with_style(mut self, style: DirectoryStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_project(mut self, project: bool) -> Self {
+        self.project = project;
+        self
+    }
+
+    pub fn with_icons(mut self, mode: IconMode) -> Self {
+        self.icon_mode = mode;
+        self.icons = get_icons(mode);
+        self
+    }
+
+    fn format_path(&self, path: &Path, project: Option<&ProjectInfo>) -> String {
+        let display = match self.style {
+            // 在项目中时以项目名代替目录名，子目录保留相对路径
+            DirectoryStyle::Name => match project {
+                Some(info) => DisplayPath::from_project(path, info),
+                None => return get_current_dir_name(path),
+            },
+            DirectoryStyle::Full => DisplayPath::from_full(path),
+            DirectoryStyle::Repo => {
+                DisplayPath::from_repo(path).unwrap_or_else(|| DisplayPath::from_full(path))
+            }
+            DirectoryStyle::Fish => DisplayPath::from_full(path).abbreviate(),
+        };
+
+        display.truncate(self.max_depth).to_string()
     }
 }
 
 impl Segment for DirectorySegment {
-    fn render(&self, input: &InputData) -> String {
+    fn name(&self) -> &'static str {
+        "directory"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
         if !self.enabled {
-            return String::new();
+            return Vec::new();
         }
 
-        let dir_name = get_current_dir_name(&input.workspace.current_dir);
-        format!("○ {}", dir_name)
+        let current_dir = input.current_dir();
+        let project = if self.project {
+            project::detect(Path::new(current_dir))
+        } else {
+            None
+        };
+        // 项目语言图标是 Nerd Font 字形，只在 nerd 图标模式下使用，其他模式使用普通的目录图标
+        let icon = match &project {
+            Some(info) if self.icon_mode == IconMode::Nerd => info.kind.icon(),
+            _ => self.icons.directory,
+        };
+        let dir_name = self.format_path(Path::new(current_dir), project.as_ref());
+        let link = self.hyperlinks.then(|| file_url(current_dir));
+        let mut spans = Vec::new();
+        if !icon.is_empty() {
+            spans.push(Span::styled(icon, Style::fg(Color::Yellow).bold()));
+            spans.push(Span::plain(" "));
+        }
+        spans.push(Span::styled(dir_name, Style::fg(Color::Green).bold()).with_link(link));
+        spans
     }
 
     fn enabled(&self) -> bool {
         self.enabled
     }
+
+    fn volatile(&self) -> bool {
+        true
+    }
+}
+
+/// 拆分后的显示路径：可选的前缀（/、~、C:、…）加上各级目录名
+struct DisplayPath {
+    prefix: Option<String>,
+    components: Vec<String>,
+}
+
+impl DisplayPath {
+    /// 完整路径，home 目录下的路径以 ~ 开头
+    fn from_full(path: &Path) -> Self {
+        if let Some(rest) = dirs::home_dir().and_then(|home| path.strip_prefix(home).ok()) {
+            return Self {
+                prefix: Some("~".to_string()),
+                components: normal_components(rest),
+            };
+        }
+
+        Self {
+            prefix: root_prefix(path),
+            components: normal_components(path),
+        }
+    }
+
+    /// 以 Git 仓库目录名开头的相对路径，不在仓库中时返回 None
+    fn from_repo(path: &Path) -> Option<Self> {
+        let root = find_repo_root(path)?;
+        let mut components = vec![get_current_dir_name(&root)];
+        components.extend(normal_components(path.strip_prefix(&root).ok()?));
+        Some(Self {
+            prefix: None,
+            components,
+        })
+    }
+
+    /// 以项目名开头、相对于项目根目录的路径
+    fn from_project(path: &Path, info: &ProjectInfo) -> Self {
+        let mut components = vec![info.name.clone()];
+        if let Ok(rest) = path.strip_prefix(&info.root) {
+            components.extend(normal_components(rest));
+        }
+        Self {
+            prefix: None,
+            components,
+        }
+    }
+
+    /// fish 风格：除最后一级外每级只保留首字符（隐藏目录保留 . 和首字符）
+    fn abbreviate(mut self) -> Self {
+        let last = self.components.len().saturating_sub(1);
+        for component in self.components.iter_mut().take(last) {
+            let keep = if component.starts_with('.') { 2 } else { 1 };
+            *component = component.chars().take(keep).collect();
+        }
+        self
+    }
+
+    /// 只保留最后 max_depth 级目录，被截断时以 … 开头
+    fn truncate(mut self, max_depth: usize) -> Self {
+        if max_depth > 0 && self.components.len() > max_depth {
+            self.components.drain(..self.components.len() - max_depth);
+            self.prefix = Some("…".to_string());
+        }
+        self
+    }
+}
+
+impl std::fmt::Display for DisplayPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let joined = self.components.join(MAIN_SEPARATOR_STR);
+        match self.prefix.as_deref() {
+            None => write!(f, "{}", joined),
+            Some(prefix) if joined.is_empty() => write!(f, "{}", prefix),
+            Some(prefix) if prefix.ends_with(MAIN_SEPARATOR) => write!(f, "{}{}", prefix, joined),
+            Some(prefix) => write!(f, "{}{}{}", prefix, MAIN_SEPARATOR, joined),
+        }
+    }
+}
+
+fn normal_components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 根目录前缀：Unix 的 /，Windows 的盘符（如 C:\）
+fn root_prefix(path: &Path) -> Option<String> {
+    let mut prefix = String::new();
+    for component in path.components() {
+        match component {
+            Component::Prefix(p) => prefix.push_str(&p.as_os_str().to_string_lossy()),
+            Component::RootDir => prefix.push(MAIN_SEPARATOR),
+            _ => break,
+        }
+    }
+    (!prefix.is_empty()).then_some(prefix)
+}
+
+fn find_repo_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
 }
 
+/// 当前目录名，根目录显示为 / 或盘符
 fn get_current_dir_name<P: AsRef<Path>>(path: P) -> String {
-    path.as_ref()
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("unknown")
-        .to_string()
+    let path = path.as_ref();
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name.to_string(),
+        None => root_prefix(path)
+            .map(|prefix| {
+                // Windows 盘符根目录显示为 C:
+                if prefix.len() > 1 {
+                    prefix.trim_end_matches(MAIN_SEPARATOR).to_string()
+                } else {
+                    prefix
+                }
+            })
+            .unwrap_or_else(|| "unknown".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_current_dir_name, DisplayPath};
+    use std::path::Path;
+
+    fn full(path: &str) -> DisplayPath {
+        DisplayPath::from_full(Path::new(path))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn full_paths_keep_the_root_prefix() {
+        assert_eq!(full("/srv/work/proj").to_string(), "/srv/work/proj");
+        assert_eq!(full("/").to_string(), "/");
+        assert_eq!(get_current_dir_name("/"), "/");
+        assert_eq!(get_current_dir_name("/srv/proj"), "proj");
+    }
+
+    #[test]
+    fn home_paths_start_with_tilde() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let path = home.join("work").join("proj");
+        let display = DisplayPath::from_full(&path);
+        assert_eq!(display.prefix.as_deref(), Some("~"));
+        assert_eq!(display.components, ["work", "proj"]);
+        assert_eq!(DisplayPath::from_full(&home).to_string(), "~");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn abbreviate_keeps_the_last_component() {
+        assert_eq!(
+            full("/srv/.config/work/packycc").abbreviate().to_string(),
+            "/s/.c/w/packycc"
+        );
+        assert_eq!(
+            full("/srv/模块/proj").abbreviate().to_string(),
+            "/s/模/proj"
+        );
+        assert_eq!(full("/").abbreviate().to_string(), "/");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn truncate_replaces_the_prefix_with_ellipsis() {
+        assert_eq!(full("/a/b/c/d").truncate(2).to_string(), "…/c/d");
+        assert_eq!(full("/a/b").truncate(2).to_string(), "/a/b");
+        assert_eq!(full("/a/b/c").truncate(0).to_string(), "/a/b/c");
+        assert_eq!(
+            full("/a/b/c/d").abbreviate().truncate(3).to_string(),
+            "…/b/c/d"
+        );
+    }
 }
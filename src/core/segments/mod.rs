@@ -1,19 +1,42 @@
+pub mod block;
+pub mod budget;
+pub mod burn_rate;
+pub mod cache;
+pub mod churn;
 pub mod cost;
+pub mod daily;
 pub mod directory;
 pub mod git;
 pub mod model;
+pub mod session;
+pub mod text_mode;
 pub mod usage;
 
 use crate::config::InputData;
+use crate::core::output::Span;
 
 pub trait Segment {
-    fn render(&self, input: &InputData) -> String;
+    /// segment 名称，用于结构化输出
+    fn name(&self) -> &'static str;
+    fn render(&self, input: &InputData) -> Vec<Span>;
     fn enabled(&self) -> bool;
+    /// 输出是否依赖输入和配置以外的状态（时钟、git、文件系统、transcript），
+    /// `replay --stable` 比较时跳过这些 segment
+    fn volatile(&self) -> bool {
+        false
+    }
 }
 
 // Re-export all segment types
+pub use block::BlockSegment;
+pub use budget::BudgetSegment;
+pub use burn_rate::BurnRateSegment;
+pub use cache::CacheSegment;
+pub use churn::ChurnSegment;
 pub use cost::CostSegment;
+pub use daily::DailySegment;
 pub use directory::DirectorySegment;
 pub use git::GitSegment;
 pub use model::ModelSegment;
+pub use session::SessionSegment;
 pub use usage::UsageSegment;
@@ -1,57 +1,61 @@
+use super::text_mode::{get_icons, Icons, UNICODE_ICONS};
 use super::Segment;
-use crate::config::InputData;
+use crate::config::{IconMode, InputData};
+use crate::core::models::ModelRegistry;
+use crate::core::output::{Color, Span, Style};
 
 pub struct ModelSegment {
     enabled: bool,
+    registry: ModelRegistry,
+    icons: Icons,
 }
 
 impl ModelSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            registry: ModelRegistry::default(),
+            icons: UNICODE_ICONS,
+        }
+    }
+
+    pub fn with_registry(mut self, registry: ModelRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_icons(mut self, mode: IconMode) -> Self {
+        self.icons = get_icons(mode);
+        self
     }
 }
 
 impl Segment for ModelSegment {
-    fn render(&self, input: &InputData) -> String {
+    fn name(&self) -> &'static str {
+        "model"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
         if !self.enabled {
-            return String::new();
+            return Vec::new();
         }
 
-        format!("● {}", self.format_model_name(&input.model.display_name))
+        let info = self.registry.resolve_model(&input.model);
+        if info.name.is_empty() {
+            return Vec::new();
+        }
+        // 模型表中自定义的图标优先
+        let icon = info.icon.as_deref().unwrap_or(self.icons.model);
+        let color = info.color.unwrap_or(Color::Cyan);
+        let text = if icon.is_empty() {
+            info.name
+        } else {
+            format!("{} {}", icon, info.name)
+        };
+        vec![Span::styled(text, Style::fg(color).bold())]
     }
 
     fn enabled(&self) -> bool {
         self.enabled
     }
 }
-
-impl ModelSegment {
-    fn format_model_name(&self, display_name: &str) -> String {
-        // 首先处理 (1M context) -> 1M
-        let name = display_name.replace("(1M context)", "1M").trim().to_string();
-
-        match name.as_str() {
-            // Opus 系列
-            n if n.contains("opus-4-5") || n.contains("opus-4.5") => "Opus 4.5".to_string(),
-            n if n.contains("claude-4-1-opus") => "Opus 4.1".to_string(),
-            n if n.contains("claude-4-opus") || n.contains("opus-4") => "Opus 4".to_string(),
-            // Sonnet 系列
-            n if n.contains("sonnet-4-5") || n.contains("sonnet-4.5") => {
-                // 如果包含 1M，保留
-                if name.contains("1M") {
-                    "Sonnet 4.5 1M".to_string()
-                } else {
-                    "Sonnet 4.5".to_string()
-                }
-            }
-            n if n.contains("claude-4-sonnet") || n.contains("sonnet-4") => "Sonnet 4".to_string(),
-            n if n.contains("claude-3-7-sonnet") => "Sonnet 3.7".to_string(),
-            n if n.contains("claude-3-5-sonnet") => "Sonnet 3.5".to_string(),
-            n if n.contains("claude-3-sonnet") => "Sonnet 3".to_string(),
-            // Haiku 系列
-            n if n.contains("haiku") => "Haiku".to_string(),
-            // 默认
-            _ => name,
-        }
-    }
-}
@@ -0,0 +1,55 @@
+use super::Segment;
+use crate::config::InputData;
+use crate::core::format::format_duration;
+use crate::core::output::{Color, Span, Style};
+
+/// 会话时长 segment，显示总时长、API 耗时及其占比（等待模型的时间比例）
+pub struct SessionSegment {
+    enabled: bool,
+}
+
+impl SessionSegment {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl Segment for SessionSegment {
+    fn name(&self) -> &'static str {
+        "session"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        let cost = match &input.cost {
+            Some(c) => c,
+            None => return Vec::new(),
+        };
+        let wall_ms = match cost.total_duration_ms {
+            Some(ms) if ms > 0 => ms,
+            _ => return Vec::new(),
+        };
+
+        let mut spans = vec![Span::styled(
+            format!("⏱ {}", format_duration(wall_ms)),
+            Style::fg(Color::Cyan),
+        )];
+
+        if let Some(api_ms) = cost.total_api_duration_ms {
+            let ratio = api_ms as f64 / wall_ms as f64 * 100.0;
+            spans.push(Span::styled(
+                format!(" · api {} ({:.0}%)", format_duration(api_ms), ratio),
+                Style::fg(Color::BrightBlack),
+            ));
+        }
+
+        spans
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
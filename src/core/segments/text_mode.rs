@@ -1,16 +1,32 @@
-// 纯文本模式的图标定义
+use crate::config::IconMode;
+
+// 各图标模式的图标定义
+#[derive(Debug, Clone, Copy)]
 pub struct Icons {
     pub model: &'static str,
     pub directory: &'static str,
     pub git: &'static str,
     pub usage: &'static str,
+    pub burn_rate: &'static str,
+    pub cost: &'static str,
 }
 
+pub const UNICODE_ICONS: Icons = Icons {
+    model: "●",
+    directory: "○",
+    git: "◐",
+    usage: "",
+    burn_rate: "▲",
+    cost: "◇",
+};
+
 pub const NERD_ICONS: Icons = Icons {
-    model: "\u{e26d}",      // 
-    directory: "\u{f024b}",  // 󰉋
-    git: "\u{f02a2}",        // 󰊢
-    usage: "\u{f49b}",       // 
+    model: "\u{e26d}",      //
+    directory: "\u{f024b}", // 󰉋
+    git: "\u{f02a2}",       // 󰊢
+    usage: "\u{f49b}",      //
+    burn_rate: "\u{f0238}", // 󰈸
+    cost: "\u{f0d6}",       //
 };
 
 pub const EMOJI_ICONS: Icons = Icons {
@@ -18,6 +34,8 @@ pub const EMOJI_ICONS: Icons = Icons {
     directory: "📁",
     git: "🌿",
     usage: "📊",
+    burn_rate: "🔥",
+    cost: "💰",
 };
 
 pub const ASCII_ICONS: Icons = Icons {
@@ -25,6 +43,8 @@ pub const ASCII_ICONS: Icons = Icons {
     directory: "[D]",
     git: "[G]",
     usage: "[U]",
+    burn_rate: "[B]",
+    cost: "[$]",
 };
 
 pub const NO_ICONS: Icons = Icons {
@@ -32,13 +52,16 @@ pub const NO_ICONS: Icons = Icons {
     directory: "",
     git: "",
     usage: "",
+    burn_rate: "",
+    cost: "",
 };
 
-pub fn get_icons(mode: &str) -> Icons {
+pub fn get_icons(mode: IconMode) -> Icons {
     match mode {
-        "emoji" => EMOJI_ICONS,
-        "ascii" => ASCII_ICONS,
-        "none" => NO_ICONS,
-        _ => NERD_ICONS,
+        IconMode::Unicode => UNICODE_ICONS,
+        IconMode::Nerd => NERD_ICONS,
+        IconMode::Emoji => EMOJI_ICONS,
+        IconMode::Ascii => ASCII_ICONS,
+        IconMode::None => NO_ICONS,
     }
-}
\ No newline at end of file
+}
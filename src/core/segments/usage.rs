@@ -1,62 +1,165 @@
+use super::text_mode::{get_icons, Icons, UNICODE_ICONS};
 use super::Segment;
-use crate::config::InputData;
+use crate::config::{BarStyle, IconMode, InputData, UsageConfig};
+use crate::core::format::format_token_count;
+use crate::core::models::{resolve_context_window, ModelRegistry};
+use crate::core::output::{Color, Span, Style};
 
 /// 上下文使用率 segment，显示当前上下文使用情况
 pub struct UsageSegment {
     enabled: bool,
+    config: UsageConfig,
+    registry: ModelRegistry,
+    icons: Icons,
 }
 
 impl UsageSegment {
     pub fn new(enabled: bool) -> Self {
-        Self { enabled }
+        Self {
+            enabled,
+            config: UsageConfig::default(),
+            registry: ModelRegistry::default(),
+            icons: UNICODE_ICONS,
+        }
+    }
+
+    pub fn with_registry(mut self, registry: ModelRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn with_config(mut self, config: UsageConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_icons(mut self, mode: IconMode) -> Self {
+        self.icons = get_icons(mode);
+        self
+    }
+
+    /// 按阈值选择颜色，达到 critical 时加粗
+    fn level_style(&self, rate: f64) -> Style {
+        let colors = &self.config.colors;
+        if rate >= self.config.critical {
+            Style::fg(colors.critical).bold()
+        } else if rate >= self.config.high {
+            Style::fg(colors.high)
+        } else if rate >= self.config.warning {
+            Style::fg(colors.warning)
+        } else {
+            Style::fg(colors.normal)
+        }
     }
 }
 
 impl Segment for UsageSegment {
-    fn render(&self, input: &InputData) -> String {
+    fn name(&self) -> &'static str {
+        "usage"
+    }
+
+    fn render(&self, input: &InputData) -> Vec<Span> {
         if !self.enabled {
-            return String::new();
+            return Vec::new();
         }
 
         // 直接从 context_window 获取数据
         let context_window = match &input.context_window {
             Some(cw) => cw,
-            None => return String::new(),
+            None => return Vec::new(),
         };
 
-        let context_limit = context_window.context_window_size.unwrap_or(200_000);
-        let context_used = match &context_window.current_usage {
-            Some(usage) => {
+        // 未报告窗口大小时按模型推断（如 1M 上下文模型）
+        let context_limit =
+            resolve_context_window(input, &self.registry, &self.config.context_windows);
+        // 没有 current_usage 时按 Claude Code 报告的使用率换算
+        let context_used = match (
+            &context_window.current_usage,
+            context_window.used_percentage,
+        ) {
+            (Some(usage), _) => {
                 usage.input_tokens
                     + usage.cache_creation_input_tokens
                     + usage.cache_read_input_tokens
             }
-            None => return String::new(),
+            (None, Some(percent)) => (percent / 100.0 * context_limit as f64).round() as u32,
+            (None, None) => return Vec::new(),
         };
 
         let context_used_rate = (context_used as f64 / context_limit as f64) * 100.0;
 
         // 格式化 token 显示（当前/总量）
-        let current_display = format_token_count(context_used);
-        let limit_display = format_token_count(context_limit);
-
-        // 生成进度条（薰衣草色进度 + 深灰色底）
-        let bar_width = 10;
-        let filled = ((context_used_rate / 100.0) * bar_width as f64).round() as usize;
-        let filled = filled.min(bar_width);
-        let empty = bar_width - filled;
-        // 薰衣草色进度 \x1b[38;5;147m，深灰色底 \x1b[90m
-        let progress_bar = format!(
-            "\x1b[38;5;147m{}\x1b[90m{}\x1b[0m",
-            "▓".repeat(filled),
-            "░".repeat(empty)
-        );
+        let current_display = format_token_count(context_used.into());
+        let limit_display = format_token_count(context_limit.into());
+        let style = self.level_style(context_used_rate);
+
+        let mut spans = Vec::new();
+        if !self.icons.usage.is_empty() {
+            spans.push(Span::styled(format!("{} ", self.icons.usage), style));
+        }
+
+        // 接近自动压缩时显示警告图标
+        let compact_at = self.config.auto_compact_percent;
+        let near_compact = compact_at > 0.0
+            && context_used_rate >= compact_at - self.config.compact_warning_margin;
+        if near_compact {
+            spans.push(Span::styled("⚠ ", Style::fg(Color::Red).bold()));
+        }
+
+        // 进度条（按阈值着色的进度 + 深灰色底）
+        if let Some((filled, empty)) = render_bar(
+            self.config.bar_style,
+            context_used_rate,
+            self.config.bar_width,
+        ) {
+            spans.push(Span::styled(filled, style));
+            spans.push(Span::styled(empty, Style::fg(Color::BrightBlack)));
+            spans.push(Span::plain(" "));
+        }
+
+        let percent = format!("{:.*}%", self.config.precision, context_used_rate);
+        let text = match self.config.bar_style {
+            BarStyle::Percent => percent,
+            BarStyle::Numeric => format!("{}/{}", current_display, limit_display),
+            _ => format!("{} ({}/{})", percent, current_display, limit_display),
+        };
+        spans.push(Span::styled(text, style));
+
+        if let Some(usage) = &context_window.current_usage {
+            // 新输入 / 缓存读取 / 缓存写入
+            if self.config.show_breakdown {
+                spans.push(Span::styled(
+                    format!(
+                        " [in {} · cr {} · cw {}]",
+                        format_token_count(usage.input_tokens.into()),
+                        format_token_count(usage.cache_read_input_tokens.into()),
+                        format_token_count(usage.cache_creation_input_tokens.into())
+                    ),
+                    Style::fg(Color::BrightBlack),
+                ));
+            }
+
+            if self.config.show_output_tokens {
+                spans.push(Span::styled(
+                    format!(" out {}", format_token_count(usage.output_tokens.into())),
+                    Style::fg(Color::BrightBlack),
+                ));
+            }
+        }
+
+        // 估算距离自动压缩还剩多少 token
+        if near_compact {
+            let compact_tokens = (compact_at / 100.0 * context_limit as f64) as u64;
+            let remaining = compact_tokens.saturating_sub(context_used.into());
+            let text = if remaining > 0 {
+                format!(" ≈{} to compact", format_token_count(remaining))
+            } else {
+                " compact due".to_string()
+            };
+            spans.push(Span::styled(text, Style::fg(Color::Red)));
+        }
 
-        // 薰衣草色的百分比和上下文大小 \x1b[38;5;147m
-        format!(
-            "{} \x1b[38;5;147m{:.2}% ({}/{})\x1b[0m",
-            progress_bar, context_used_rate, current_display, limit_display
-        )
+        spans
     }
 
     fn enabled(&self) -> bool {
@@ -64,13 +167,87 @@ impl Segment for UsageSegment {
     }
 }
 
-/// 格式化 token 数量显示
-fn format_token_count(tokens: u32) -> String {
-    if tokens >= 1_000_000 {
-        format!("{:.2}M", tokens as f64 / 1_000_000.0)
-    } else if tokens >= 1000 {
-        format!("{:.2}K", tokens as f64 / 1000.0)
-    } else {
-        tokens.to_string()
+/// 八分之一格的部分填充字符，索引为填充的八分之几
+const EIGHTHS: [&str; 8] = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"];
+/// 盲文点阵的部分填充字符，索引为点亮的点数
+const BRAILLE: [&str; 8] = ["", "⡀", "⡄", "⡆", "⡇", "⣇", "⣧", "⣷"];
+
+/// 生成进度条的已填充和未填充部分，percent / numeric 样式不显示进度条
+fn render_bar(style: BarStyle, rate: f64, width: usize) -> Option<(String, String)> {
+    let (full, partials, empty) = match style {
+        BarStyle::Blocks => ("▓", None, "░"),
+        BarStyle::Eighths => ("█", Some(&EIGHTHS), "░"),
+        BarStyle::Braille => ("⣿", Some(&BRAILLE), "⣀"),
+        BarStyle::Dots => ("●", None, "○"),
+        BarStyle::Percent | BarStyle::Numeric => return None,
+    };
+
+    let cells = (rate.clamp(0.0, 100.0) / 100.0) * width as f64;
+    let steps = if partials.is_some() { 8 } else { 1 };
+    // 按格（或八分之一格）取整，未满 100% 时至少留出一份空位，99% 不会显示成满格
+    let mut filled = (cells * steps as f64).round() as usize;
+    if rate < 100.0 {
+        filled = filled.min((width * steps).saturating_sub(1));
+    }
+    let filled_cells = (filled / steps).min(width);
+    let partial = match partials {
+        Some(partials) if filled_cells < width => partials[filled % steps],
+        _ => "",
+    };
+
+    let used = filled_cells + usize::from(!partial.is_empty());
+    Some((
+        full.repeat(filled_cells) + partial,
+        empty.repeat(width - used),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_bar;
+    use crate::config::BarStyle;
+
+    fn bar(style: BarStyle, rate: f64) -> String {
+        let (filled, empty) = render_bar(style, rate, 10).unwrap();
+        format!("{}{}", filled, empty)
+    }
+
+    #[test]
+    fn blocks_fill_by_whole_cells() {
+        assert_eq!(bar(BarStyle::Blocks, 0.0), "░░░░░░░░░░");
+        assert_eq!(bar(BarStyle::Blocks, 42.0), "▓▓▓▓░░░░░░");
+        assert_eq!(bar(BarStyle::Blocks, 99.0), "▓▓▓▓▓▓▓▓▓░");
+        assert_eq!(bar(BarStyle::Blocks, 100.0), "▓▓▓▓▓▓▓▓▓▓");
+        assert_eq!(bar(BarStyle::Blocks, 250.0), "▓▓▓▓▓▓▓▓▓▓");
+        assert_eq!(bar(BarStyle::Dots, -5.0), "○○○○○○○○○○");
+    }
+
+    #[test]
+    fn eighths_use_partial_cells() {
+        assert_eq!(bar(BarStyle::Eighths, 0.0), "░░░░░░░░░░");
+        assert_eq!(bar(BarStyle::Eighths, 45.0), "████▌░░░░░");
+        assert_eq!(bar(BarStyle::Eighths, 99.0), "█████████▉");
+        assert_eq!(bar(BarStyle::Eighths, 99.99), "█████████▉");
+        assert_eq!(bar(BarStyle::Eighths, 100.0), "██████████");
+        assert_eq!(bar(BarStyle::Braille, 120.0), "⣿⣿⣿⣿⣿⣿⣿⣿⣿⣿");
+    }
+
+    #[test]
+    fn bars_keep_their_width() {
+        for style in [
+            BarStyle::Blocks,
+            BarStyle::Eighths,
+            BarStyle::Braille,
+            BarStyle::Dots,
+        ] {
+            for rate in [0.0, 0.1, 12.5, 50.0, 99.0, 99.9, 100.0, 180.0] {
+                assert_eq!(bar(style, rate).chars().count(), 10, "{:?} {}", style, rate);
+            }
+        }
+        assert!(render_bar(BarStyle::Percent, 50.0, 10).is_none());
+        assert_eq!(
+            render_bar(BarStyle::Blocks, 50.0, 0),
+            Some((String::new(), String::new()))
+        );
     }
 }
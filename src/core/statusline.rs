@@ -1,59 +1,312 @@
-use crate::config::{Config, InputData};
-use crate::core::segments::{CostSegment, DirectorySegment, GitSegment, ModelSegment, Segment, UsageSegment};
+use crate::config::{Config, HyperlinkMode, InputData};
+use crate::core::blocks;
+use crate::core::budget::{self, BudgetLevel, SharedStatuses};
+use crate::core::daily;
+use crate::core::index::SharedEntries;
+use crate::core::models::ModelRegistry;
+use crate::core::output::{
+    terminal_supports_hyperlinks, Color, OutputMode, RenderedSegment, Style,
+};
+use crate::core::pricing::CostEngine;
+use crate::core::segments::{
+    BlockSegment, BudgetSegment, BurnRateSegment, CacheSegment, ChurnSegment, CostSegment,
+    DailySegment, DirectorySegment, GitSegment, ModelSegment, Segment, SessionSegment,
+    UsageSegment,
+};
+use crate::core::theme::Theme;
+use chrono::Utc;
+use std::time::{Duration, Instant};
+
+/// 所有 segment 的名称，按默认显示顺序排列
+pub const SEGMENT_NAMES: [&str; 12] = [
+    "model",
+    "directory",
+    "git",
+    "usage",
+    "cost",
+    "churn",
+    "burn_rate",
+    "session",
+    "cache",
+    "daily",
+    "block",
+    "budget",
+];
+
+/// 一次渲染中各 segment 共享的数据
+struct Shared {
+    usage: SharedEntries,
+    budgets: SharedStatuses,
+}
 
 pub struct StatusLineGenerator {
     config: Config,
+    output: OutputMode,
+    theme: Theme,
+    alerts: bool,
 }
 
 impl StatusLineGenerator {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        // 未知的主题名回退到默认主题
+        let theme = Theme::from_name(&config.theme).unwrap_or_default();
+        Self {
+            config,
+            output: OutputMode::default(),
+            theme,
+            alerts: true,
+        }
     }
 
-    pub fn generate(&self, input: &InputData) -> String {
-        let mut segments = Vec::new();
+    pub fn with_output(mut self, output: OutputMode) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// 是否执行预算告警命令，诊断和预览时关闭
+    pub fn with_alerts(mut self, alerts: bool) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// 当前输出是否应该带上 OSC 8 超链接
+    fn hyperlinks_enabled(&self) -> bool {
+        if !self.output.supports_hyperlinks() {
+            return false;
+        }
+
+        match self.config.hyperlinks {
+            HyperlinkMode::Always => true,
+            HyperlinkMode::Never => false,
+            HyperlinkMode::Auto => {
+                self.output == OutputMode::Json || terminal_supports_hyperlinks()
+            }
+        }
+    }
+
+    /// 按配置顺序渲染所有启用的 segment，跳过没有内容的 segment
+    pub fn collect(&self, input: &InputData) -> Vec<RenderedSegment> {
+        self.render(input).0
+    }
+
+    /// 渲染所有 segment，同时返回最严重的预算级别
+    fn render(&self, input: &InputData) -> (Vec<RenderedSegment>, BudgetLevel) {
+        let shared = self.shared();
+        let mut segments: Vec<RenderedSegment> = self
+            .segments(&shared)
+            .iter()
+            .map(|segment| RenderedSegment::new(segment.name(), segment.render(input)))
+            .filter(|segment| !segment.spans.is_empty())
+            .collect();
+        self.theme.apply(&mut segments);
+
+        // 预算 segment 隐藏时也检查预算，告警和整行的警告色不依赖该 segment
+        if !budget::is_configured(&self.config.budget) {
+            return (segments, BudgetLevel::Normal);
+        }
+        if self.alerts {
+            budget::notify(shared.budgets.get(input), &self.config.budget);
+        }
+        (segments, shared.budgets.level(input))
+    }
+
+    /// 启用的 segment 中输出依赖输入和配置以外状态的名称
+    pub fn volatile_segments(&self) -> Vec<&'static str> {
+        self.segments(&self.shared())
+            .iter()
+            .filter(|segment| segment.volatile())
+            .map(|segment| segment.name())
+            .collect()
+    }
+
+    /// 逐个渲染启用的 segment 并计时，返回名称、耗时和是否有输出
+    pub fn timings(&self, input: &InputData) -> Vec<(&'static str, Duration, bool)> {
+        self.segments(&self.shared())
+            .iter()
+            .map(|segment| {
+                let start = Instant::now();
+                let spans = segment.render(input);
+                (segment.name(), start.elapsed(), !spans.is_empty())
+            })
+            .collect()
+    }
+
+    /// 当天费用、计费块和日/周/月预算需要的最早用量记录时间，都未启用时返回 None
+    fn usage_since(&self) -> Option<i64> {
+        let now = Utc::now();
+        let segments = &self.config.segments;
+        [
+            segments
+                .daily
+                .then(|| daily::day_start(now, &self.config.daily).timestamp()),
+            segments
+                .block
+                .then(|| now.timestamp() - blocks::LOOKBACK_SECS),
+            budget::usage_since(&self.config),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// 同一次渲染中各 segment 共享的用量索引和预算状态
+    fn shared(&self) -> Shared {
+        let usage = self
+            .usage_since()
+            .map(SharedEntries::new)
+            .unwrap_or_default();
+        Shared {
+            budgets: SharedStatuses::new(self.config.clone(), usage.clone()),
+            usage,
+        }
+    }
+
+    fn segments(&self, shared: &Shared) -> Vec<Box<dyn Segment>> {
+        let hyperlinks = self.hyperlinks_enabled();
+        let usage = &shared.usage;
+        let mut segments: Vec<Box<dyn Segment>> = Vec::new();
 
-        // Assemble segments with proper colors
         if self.config.segments.model {
-            let model_segment = ModelSegment::new(true);
-            let content = model_segment.render(input);
-            segments.push(format!("\x1b[1;36m{}\x1b[0m", content));
+            segments.push(Box::new(
+                ModelSegment::new(true)
+                    .with_registry(ModelRegistry::new(&self.config.models))
+                    .with_icons(self.config.icons),
+            ));
         }
 
         if self.config.segments.directory {
-            let dir_segment = DirectorySegment::new(true);
-            let content = dir_segment.render(input);
-            // Extract directory name without icon
-            let dir_name = content.trim_start_matches('○').trim_start();
-            segments.push(format!("\x1b[1;33m○\x1b[0m \x1b[1;32m{}\x1b[0m", dir_name));
+            segments.push(Box::new(
+                DirectorySegment::new(true)
+                    .with_hyperlinks(hyperlinks)
+                    .with_style(self.config.directory.style)
+                    .with_max_depth(self.config.directory.max_depth)
+                    .with_project(self.config.directory.project)
+                    .with_icons(self.config.icons),
+            ));
         }
 
         if self.config.segments.git {
-            let git_segment = GitSegment::new(true);
-            let git_output = git_segment.render(input);
-            if !git_output.is_empty() {
-                segments.push(format!("\x1b[1;34m{}\x1b[0m", git_output));
-            }
+            segments.push(Box::new(
+                GitSegment::new(true)
+                    .with_hyperlinks(hyperlinks)
+                    .with_icons(self.config.icons),
+            ));
         }
 
         if self.config.segments.usage {
-            let usage_segment = UsageSegment::new(true);
-            let content = usage_segment.render(input);
-            if !content.is_empty() {
-                // 颜色已在 UsageSegment 内部设置
-                segments.push(content);
-            }
+            segments.push(Box::new(
+                UsageSegment::new(true)
+                    .with_config(self.config.usage.clone())
+                    .with_registry(ModelRegistry::new(&self.config.models))
+                    .with_icons(self.config.icons),
+            ));
         }
 
         if self.config.segments.cost {
-            let cost_segment = CostSegment::new(true);
-            let content = cost_segment.render(input);
-            if !content.is_empty() {
-                segments.push(format!("\x1b[1;33m💰{}\x1b[0m", content));
-            }
+            let engine =
+                CostEngine::from_config(ModelRegistry::new(&self.config.models), &self.config.cost);
+            segments.push(Box::new(
+                CostSegment::new(true)
+                    .with_engine(engine)
+                    .with_breakdown(self.config.cost.breakdown)
+                    .with_verify(self.config.cost.verify)
+                    .with_icons(self.config.icons),
+            ));
+        }
+
+        if self.config.segments.churn {
+            segments.push(Box::new(
+                ChurnSegment::new(true).with_config(self.config.churn.clone()),
+            ));
         }
 
-        // Join segments with white separator
-        segments.join("\x1b[37m | \x1b[0m")
+        if self.config.segments.burn_rate {
+            segments.push(Box::new(
+                BurnRateSegment::new(true)
+                    .with_config(self.config.burn_rate.clone())
+                    .with_icons(self.config.icons),
+            ));
+        }
+
+        if self.config.segments.session {
+            segments.push(Box::new(SessionSegment::new(true)));
+        }
+
+        if self.config.segments.cache {
+            let engine =
+                CostEngine::from_config(ModelRegistry::new(&self.config.models), &self.config.cost);
+            segments.push(Box::new(CacheSegment::new(true).with_engine(engine)));
+        }
+
+        if self.config.segments.daily {
+            let engine =
+                CostEngine::from_config(ModelRegistry::new(&self.config.models), &self.config.cost);
+            segments.push(Box::new(
+                DailySegment::new(true)
+                    .with_engine(engine)
+                    .with_config(self.config.daily.clone())
+                    .with_usage(usage.clone()),
+            ));
+        }
+
+        if self.config.segments.block {
+            let engine =
+                CostEngine::from_config(ModelRegistry::new(&self.config.models), &self.config.cost);
+            segments.push(Box::new(
+                BlockSegment::new(true)
+                    .with_engine(engine)
+                    .with_config(self.config.block.clone())
+                    .with_usage(usage.clone()),
+            ));
+        }
+
+        if self.config.segments.budget {
+            segments.push(Box::new(
+                BudgetSegment::new(true)
+                    .with_statuses(shared.budgets.clone())
+                    .with_hyperlinks(hyperlinks),
+            ));
+        }
+
+        // 稳定排序，未在 order 中列出的 segment 保持默认的相对顺序
+        let order = &self.config.segments.order;
+        if !order.is_empty() {
+            segments.sort_by_key(|segment| {
+                order
+                    .iter()
+                    .position(|name| name == segment.name())
+                    .unwrap_or(order.len())
+            });
+        }
+
+        segments
+    }
+
+    /// 预算进入 warning / critical 时分隔符随之变色，预算 segment 隐藏时也能看到
+    pub fn generate(&self, input: &InputData) -> String {
+        self.generate_with_segments(input).0
+    }
+
+    /// 同 generate，同时返回渲染出的各 segment，供记录使用
+    pub fn generate_with_segments(&self, input: &InputData) -> (String, Vec<RenderedSegment>) {
+        let (segments, separator) = self.collect_with_separator(input);
+        let output = self.output.format_with(&segments, separator);
+        (output, segments)
+    }
+
+    /// 同 collect，同时返回按预算级别和主题确定的分隔符样式，配置界面的预览与实际输出一致
+    pub fn collect_with_separator(&self, input: &InputData) -> (Vec<RenderedSegment>, Style) {
+        let (segments, level) = self.render(input);
+        let separator = match level {
+            BudgetLevel::Normal => Style::fg(Color::White),
+            BudgetLevel::Warning => Style::fg(Color::Yellow).bold(),
+            BudgetLevel::Critical => Style::fg(Color::Red).bold(),
+        };
+        (segments, self.theme.style(separator))
     }
 }
@@ -0,0 +1,65 @@
+use crate::core::output::{Color, RenderedSegment, Style};
+use clap::ValueEnum;
+
+/// 内置主题，通过重新映射 segment 使用的颜色实现
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Theme {
+    /// Colors tuned for dark terminal backgrounds
+    #[default]
+    Dark,
+    /// Darker shades that stay readable on light backgrounds
+    Light,
+    /// No colors, only bold text
+    Mono,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::Mono];
+
+    /// 按名称查找主题，未知名称返回 None
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::from_str(name.trim(), true).ok()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::Mono => "mono",
+        }
+    }
+
+    pub fn style(&self, style: Style) -> Style {
+        Style {
+            fg: style.fg.and_then(|color| self.color(color)),
+            bold: style.bold,
+        }
+    }
+
+    /// 将所有 span 的颜色替换为主题中的颜色
+    pub fn apply(&self, segments: &mut [RenderedSegment]) {
+        for span in segments.iter_mut().flat_map(|s| s.spans.iter_mut()) {
+            span.style = self.style(span.style);
+        }
+    }
+
+    fn color(&self, color: Color) -> Option<Color> {
+        match self {
+            Theme::Dark => Some(color),
+            Theme::Mono => None,
+            // 浅色背景上白色、亮色和浅灰都看不清，换成同色系的深色
+            Theme::Light => Some(match color {
+                Color::White | Color::BrightWhite => Color::Black,
+                Color::BrightBlack => Color::Fixed(242),
+                Color::Yellow | Color::BrightYellow => Color::Fixed(130),
+                Color::Cyan | Color::BrightCyan => Color::Fixed(30),
+                Color::Green | Color::BrightGreen => Color::Fixed(28),
+                Color::BrightBlue => Color::Blue,
+                Color::BrightMagenta => Color::Magenta,
+                Color::BrightRed => Color::Red,
+                Color::Fixed(n) if n >= 244 => Color::Fixed(240),
+                other => other,
+            }),
+        }
+    }
+}
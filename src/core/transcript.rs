@@ -0,0 +1,157 @@
+use crate::core::claude;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// 单条 assistant 消息的 token 用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input_tokens
+            + self.output_tokens
+            + self.cache_creation_input_tokens
+            + self.cache_read_input_tokens
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+    }
+}
+
+/// transcript 中一条带用量的 assistant 消息
+#[derive(Debug, Clone)]
+pub struct UsageEntry {
+    pub timestamp: Option<String>,
+    pub model: String,
+    pub usage: TokenUsage,
+    /// message.id + requestId，用于去重流式输出产生的重复行
+    pub key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptLine {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    timestamp: Option<String>,
+    #[serde(rename = "requestId")]
+    request_id: Option<String>,
+    message: Option<TranscriptMessage>,
+}
+
+#[derive(Deserialize)]
+struct TranscriptMessage {
+    id: Option<String>,
+    model: Option<String>,
+    usage: Option<TokenUsage>,
+}
+
+/// 解析 transcript 的一行，非 assistant 消息或没有用量时返回 None
+pub fn parse_line(line: &str) -> Option<UsageEntry> {
+    // 快速跳过不含用量的行，避免对每一行做完整的 JSON 解析
+    if !line.contains("\"usage\"") {
+        return None;
+    }
+
+    let parsed: TranscriptLine = serde_json::from_str(line).ok()?;
+    if parsed.kind.as_deref() != Some("assistant") {
+        return None;
+    }
+
+    let message = parsed.message?;
+    let model = message.model.unwrap_or_default();
+    // Claude Code 为本地生成的消息写入 <synthetic>，不计费
+    if model.is_empty() || model == "<synthetic>" {
+        return None;
+    }
+
+    let key = match (message.id, parsed.request_id) {
+        (Some(id), Some(request)) => Some(format!("{}:{}", id, request)),
+        (Some(id), None) => Some(id),
+        _ => None,
+    };
+
+    Some(UsageEntry {
+        timestamp: parsed.timestamp,
+        model,
+        usage: message.usage?,
+        key,
+    })
+}
+
+/// 读取 transcript 中的所有用量记录，同一消息重复出现时保留最后一次
+pub fn read_usage_entries<P: AsRef<Path>>(path: P) -> Vec<UsageEntry> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<UsageEntry> = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        let Some(entry) = parse_line(&line) else {
+            continue;
+        };
+
+        match entry.key.as_ref().and_then(|key| seen.get(key)) {
+            Some(&index) => entries[index] = entry,
+            None => {
+                if let Some(key) = entry.key.clone() {
+                    seen.insert(key, entries.len());
+                }
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+/// 同一次渲染中多个 segment 共享的解析结果，每个 transcript 只读取一次
+pub fn session_entries(path: &str) -> Arc<Vec<UsageEntry>> {
+    static SESSIONS: OnceLock<Mutex<HashMap<PathBuf, Arc<Vec<UsageEntry>>>>> = OnceLock::new();
+
+    let path = PathBuf::from(path);
+    let mut sessions = SESSIONS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    sessions
+        .entry(path)
+        .or_insert_with_key(|path| Arc::new(read_usage_entries(path)))
+        .clone()
+}
+
+/// 所有 transcript 文件（递归查找 *.jsonl）
+pub fn transcript_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for dir in claude::projects_dirs() {
+        collect_jsonl(&dir, &mut files);
+    }
+    files
+}
+
+fn collect_jsonl(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_jsonl(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            files.push(path);
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod cli;
 pub mod config;
 pub mod core;
+#[cfg(feature = "tui")]
+pub mod ui;
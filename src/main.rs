@@ -1,15 +1,31 @@
-use ccometixline::cli::Cli;
-use ccometixline::config::{Config, ConfigLoader, InputData};
-use ccometixline::core::StatusLineGenerator;
-use std::io;
+use ccometixline::cli::{Cli, Command};
+use ccometixline::config::{template, Config, InputData, LayeredConfig};
+use ccometixline::core::doctor::{self, CheckStatus};
+use ccometixline::core::install::{self, InstallOptions};
+use ccometixline::core::record;
+use ccometixline::core::report::{self, ReportOptions};
+use ccometixline::core::theme::Theme;
+use ccometixline::core::{history, sample, StatusLineGenerator};
+use std::fs;
+use std::io::{self, IsTerminal, Read};
+use std::path::Path;
+use std::process;
 
 fn main() -> io::Result<()> {
     let cli = Cli::parse_args();
 
     // Handle special CLI modes
     if cli.print_config {
-        let config = Config::default();
-        println!("{}", toml::to_string(&config).unwrap());
+        let output = if cli.template {
+            template::template()
+        } else if cli.default {
+            toml::to_string(&Config::default()).map_err(|e| io::Error::other(e.to_string()))?
+        } else {
+            LayeredConfig::load(cli.config.as_deref(), cli.overrides())
+                .and_then(|layers| layers.annotated())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?
+        };
+        print!("{}", output);
         return Ok(());
     }
 
@@ -19,22 +35,145 @@ fn main() -> io::Result<()> {
     }
 
     if cli.configure {
-        println!("TUI configuration mode not implemented yet");
+        #[cfg(feature = "tui")]
+        ccometixline::ui::run(cli.config.as_deref())
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        #[cfg(not(feature = "tui"))]
+        eprintln!("ccline was built without the tui feature; edit the config file directly");
         return Ok(());
     }
 
-    // Load configuration
-    let config = ConfigLoader::load();
+    // Claude Code settings.json integration
+    let install_result = match &cli.command {
+        Some(Command::Install(args)) => Some(install::install(&InstallOptions {
+            scope: args.scope,
+            dry_run: args.dry_run,
+            command: args.command.clone(),
+            force: false,
+        })),
+        Some(Command::Uninstall(args)) => Some(install::uninstall(&InstallOptions {
+            scope: args.scope,
+            dry_run: args.dry_run,
+            command: None,
+            force: args.force,
+        })),
+        _ => None,
+    };
+    if let Some(result) = install_result {
+        let message = result.map_err(|e| io::Error::other(e.to_string()))?;
+        println!("{}", message);
+        return Ok(());
+    }
+
+    // Environment diagnostics, run before loading so config errors can be reported
+    if let Some(Command::Doctor(args)) = &cli.command {
+        let input = match &args.input {
+            Some(path) => read_input(path)?,
+            None => sample::sample_input(),
+        };
+        let checks = doctor::run(cli.config.as_deref(), &input);
+        println!("{}", doctor::format(&checks, io::stdout().is_terminal()));
+        if checks.iter().any(|c| c.status == CheckStatus::Fail) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Load configuration: defaults, then the config file, then command line options
+    let config = match LayeredConfig::load(cli.config.as_deref(), cli.overrides()) {
+        Ok(layers) => layers.config,
+        Err(e) if cli.config.is_some() => {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+        }
+        // A broken default config file must not break the statusline
+        Err(_) => LayeredConfig::without_file(cli.overrides()).config,
+    };
+
+    if let Some(Command::Report(args)) = &cli.command {
+        let options = ReportOptions {
+            group: args.by,
+            format: args.format,
+            source: args.source,
+            since: args.since,
+            until: args.until,
+        };
+        let output = report::generate(&config, &options)?;
+        println!("{}", output);
+        return Ok(());
+    }
+
+    // Render sample or saved input without Claude Code
+    if let Some(Command::Preview(args)) = &cli.command {
+        let scenario = args.scenario.map(sample::scenario_input).transpose()?;
+        let loaded;
+        let input = match (&args.input, &scenario) {
+            (Some(path), _) => {
+                loaded = read_input(path)?;
+                &loaded
+            }
+            (None, Some(scenario)) => &scenario.input,
+            (None, None) => {
+                loaded = sample::sample_input();
+                &loaded
+            }
+        };
+
+        let themes = if args.all_themes {
+            Theme::ALL.to_vec()
+        } else {
+            vec![Theme::from_name(&config.theme).unwrap_or_default()]
+        };
+        for theme in themes {
+            let generator = StatusLineGenerator::new(config.clone())
+                .with_output(cli.output)
+                .with_theme(theme)
+                .with_alerts(false);
+            let statusline = generator.generate(input);
+            if args.all_themes {
+                println!("{:<6} {}", theme.name(), statusline);
+            } else {
+                println!("{}", statusline);
+            }
+        }
+        return Ok(());
+    }
+
+    // Re-render recorded inputs with the current config
+    if let Some(Command::Replay(args)) = &cli.command {
+        let recordings = record::read_recordings(Path::new(&args.file))?;
+        let replay = record::replay(&recordings, &config, cli.output, args.diff, args.stable);
+        println!("{}", replay.text);
+        if args.diff && replay.changed > 0 {
+            process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Read Claude Code data from stdin
     let stdin = io::stdin();
-    let input: InputData = serde_json::from_reader(stdin.lock())?;
+    if stdin.is_terminal() {
+        eprintln!("ccline expects Claude Code statusline JSON on stdin; run `ccline preview` to see a sample");
+        process::exit(2);
+    }
+    let mut raw = String::new();
+    stdin.lock().read_to_string(&mut raw)?;
+    let input: InputData = serde_json::from_str(&raw)?;
+
+    // Record the session snapshot for usage reports
+    history::record(&input, &config);
 
     // Generate statusline
-    let generator = StatusLineGenerator::new(config);
-    let statusline = generator.generate(&input);
+    let generator = StatusLineGenerator::new(config.clone()).with_output(cli.output);
+    let (statusline, segments) = generator.generate_with_segments(&input);
+
+    // Save the input and output for replay when recording is enabled
+    record::record(&raw, cli.output, &segments, &statusline, &config.record);
 
     println!("{}", statusline);
 
     Ok(())
 }
+
+fn read_input(path: &str) -> io::Result<InputData> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
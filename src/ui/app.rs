@@ -0,0 +1,566 @@
+use crate::config::{Config, InputData};
+use crate::core::output::{RenderedSegment, Style as SegmentStyle};
+use crate::core::sample;
+use crate::core::statusline::SEGMENT_NAMES;
+use crate::core::StatusLineGenerator;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use toml_edit::{Array, DocumentMut, Item, Table, TableLike, Value};
+
+/// 选项的取值类型
+#[derive(Debug, Clone, Copy)]
+pub enum OptionKind {
+    Bool,
+    Choice(&'static [&'static str]),
+    Integer,
+    Float,
+    Text,
+}
+
+/// 一个可编辑的配置项，key 为 TOML 中的点分路径
+#[derive(Debug, Clone, Copy)]
+pub struct OptionSpec {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub kind: OptionKind,
+}
+
+// 使用宏展开为结构体字面量，数组可以提升为 'static
+macro_rules! option {
+    ($key:expr, $label:expr, $kind:expr $(,)?) => {
+        OptionSpec {
+            key: $key,
+            label: $label,
+            kind: $kind,
+        }
+    };
+}
+
+const GENERAL_OPTIONS: &[OptionSpec] = &[
+    option!(
+        "theme",
+        "Theme",
+        OptionKind::Choice(&["dark", "light", "mono"]),
+    ),
+    option!(
+        "icons",
+        "Icons",
+        OptionKind::Choice(&["unicode", "nerd", "emoji", "ascii", "none"]),
+    ),
+    option!(
+        "hyperlinks",
+        "Hyperlinks",
+        OptionKind::Choice(&["auto", "always", "never"]),
+    ),
+];
+
+/// 每个 segment 可在界面中编辑的选项
+fn segment_options(name: &str) -> &'static [OptionSpec] {
+    match name {
+        "directory" => &[
+            option!(
+                "directory.style",
+                "Style",
+                OptionKind::Choice(&["name", "full", "repo", "fish"]),
+            ),
+            option!("directory.max_depth", "Max depth", OptionKind::Integer),
+            option!("directory.project", "Project name", OptionKind::Bool),
+        ],
+        "usage" => &[
+            option!(
+                "usage.bar_style",
+                "Bar style",
+                OptionKind::Choice(
+                    &["blocks", "eighths", "braille", "dots", "percent", "numeric",]
+                ),
+            ),
+            option!("usage.bar_width", "Bar width", OptionKind::Integer),
+            option!("usage.precision", "Precision", OptionKind::Integer),
+            option!("usage.warning", "Warning %", OptionKind::Float),
+            option!("usage.high", "High %", OptionKind::Float),
+            option!("usage.critical", "Critical %", OptionKind::Float),
+            option!(
+                "usage.auto_compact_percent",
+                "Auto compact %",
+                OptionKind::Float,
+            ),
+            option!("usage.show_breakdown", "Token breakdown", OptionKind::Bool),
+            option!(
+                "usage.show_output_tokens",
+                "Output tokens",
+                OptionKind::Bool,
+            ),
+        ],
+        "cost" => &[
+            option!("cost.breakdown", "Breakdown", OptionKind::Bool),
+            option!("cost.verify", "Verify", OptionKind::Bool),
+        ],
+        "churn" => &[
+            option!("churn.net", "Net lines", OptionKind::Bool),
+            option!(
+                "churn.cost_per_100_lines",
+                "Cost per 100 lines",
+                OptionKind::Bool,
+            ),
+            option!("churn.git_diff", "Git diff", OptionKind::Bool),
+        ],
+        "burn_rate" => &[
+            option!(
+                "burn_rate.projection_hours",
+                "Projection hours",
+                OptionKind::Float,
+            ),
+            option!(
+                "burn_rate.warn_cost_per_hour",
+                "Warn $/h",
+                OptionKind::Float,
+            ),
+            option!(
+                "burn_rate.critical_cost_per_hour",
+                "Critical $/h",
+                OptionKind::Float,
+            ),
+        ],
+        "daily" => &[
+            option!(
+                "daily.day_start_hour",
+                "Day start hour",
+                OptionKind::Integer,
+            ),
+            option!("daily.timezone", "Timezone", OptionKind::Text),
+        ],
+        "block" => &[
+            option!("block.token_limit", "Token limit", OptionKind::Integer),
+            option!("block.cost_limit", "Cost limit", OptionKind::Float),
+        ],
+        "budget" => &[
+            option!("budget.session", "Session $", OptionKind::Float),
+            option!("budget.daily", "Daily $", OptionKind::Float),
+            option!("budget.weekly", "Weekly $", OptionKind::Float),
+            option!("budget.monthly", "Monthly $", OptionKind::Float),
+            option!("budget.warning_percent", "Warning %", OptionKind::Float),
+            option!("budget.critical_percent", "Critical %", OptionKind::Float),
+            option!(
+                "budget.source",
+                "Source",
+                OptionKind::Choice(&["local", "packycode"]),
+            ),
+            option!("budget.command", "Alert command", OptionKind::Text),
+        ],
+        _ => &[],
+    }
+}
+
+/// 左侧列表的一行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Row {
+    General,
+    Segment(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Rows,
+    Options,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Edit,
+    /// 保存前确认修改
+    Diff,
+}
+
+pub struct App {
+    pub path: PathBuf,
+    /// 配置文件当前在磁盘上的内容，文件不存在时为空
+    original: String,
+    /// 正在编辑的文档，保留用户原有的注释和格式
+    doc: DocumentMut,
+    pub config: Config,
+    /// 合并默认值后的配置，用于显示当前值
+    effective: toml::Value,
+    input: InputData,
+    pub preview: Vec<RenderedSegment>,
+    /// 预览中分隔符的样式，随主题和预算级别变化
+    pub preview_separator: SegmentStyle,
+    pub rows: Vec<Row>,
+    pub selected: usize,
+    pub focus: Focus,
+    pub option: usize,
+    /// 正在输入的文本，Some 时处于编辑状态
+    pub editing: Option<String>,
+    pub view: View,
+    pub diff_scroll: u16,
+    pub message: Option<String>,
+    quit_armed: bool,
+    pub done: bool,
+}
+
+impl App {
+    pub fn new(path: PathBuf) -> Result<Self, Box<dyn std::error::Error>> {
+        let original = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let doc: DocumentMut = original.parse()?;
+        let config: Config = toml::from_str(&original)?;
+
+        let mut app = Self {
+            path,
+            original,
+            doc,
+            config,
+            effective: toml::Value::Table(Default::default()),
+            input: sample::sample_input(),
+            preview: Vec::new(),
+            preview_separator: SegmentStyle::default(),
+            rows: Vec::new(),
+            selected: 0,
+            focus: Focus::Rows,
+            option: 0,
+            editing: None,
+            view: View::Edit,
+            diff_scroll: 0,
+            message: None,
+            quit_armed: false,
+            done: false,
+        };
+        app.refresh();
+        Ok(app)
+    }
+
+    /// 配置变化后重新计算列表顺序、当前值和预览
+    fn refresh(&mut self) {
+        let order = &self.config.segments.order;
+        let mut names: Vec<&'static str> = SEGMENT_NAMES.to_vec();
+        names.sort_by_key(|name| order.iter().position(|o| o == name).unwrap_or(order.len()));
+        self.rows = std::iter::once(Row::General)
+            .chain(names.into_iter().map(Row::Segment))
+            .collect();
+
+        self.effective = toml::Value::try_from(&self.config)
+            .unwrap_or_else(|_| toml::Value::Table(Default::default()));
+        (self.preview, self.preview_separator) = StatusLineGenerator::new(self.config.clone())
+            .with_alerts(false)
+            .collect_with_separator(&self.input);
+    }
+
+    pub fn row(&self) -> Row {
+        self.rows[self.selected]
+    }
+
+    pub fn options(&self) -> &'static [OptionSpec] {
+        match self.row() {
+            Row::General => GENERAL_OPTIONS,
+            Row::Segment(name) => segment_options(name),
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.lookup(&format!("segments.{}", name))
+            .and_then(toml::Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    /// 当前生效的值，未设置的可选项返回 None
+    pub fn value_text(&self, spec: &OptionSpec) -> Option<String> {
+        self.lookup(spec.key).map(|value| match value {
+            toml::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+    }
+
+    fn lookup(&self, key: &str) -> Option<&toml::Value> {
+        key.split('.')
+            .try_fold(&self.effective, |value, part| value.get(part))
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.doc.to_string() != self.original
+    }
+
+    pub fn new_content(&self) -> String {
+        self.doc.to_string()
+    }
+
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// 修改文档中的一个值（None 表示删除，回到默认值），无法解析时撤销修改
+    fn set(&mut self, key: &str, value: Option<Value>) {
+        let previous = self.doc.clone();
+        if !set_path(&mut self.doc, key, value) {
+            self.message = Some(format!("cannot set {}: not a table", key));
+            return;
+        }
+        match toml::from_str::<Config>(&self.doc.to_string()) {
+            Ok(config) => {
+                self.config = config;
+                self.message = None;
+                self.refresh();
+            }
+            Err(e) => {
+                self.doc = previous;
+                self.message = Some(format!("invalid value for {}: {}", key, e.message()));
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.editing.is_some() {
+            self.handle_edit_key(key);
+            return;
+        }
+        match self.view {
+            View::Edit => self.handle_browse_key(key),
+            View::Diff => self.handle_diff_key(key),
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) {
+        let quit_armed = std::mem::take(&mut self.quit_armed);
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if !self.is_dirty() || quit_armed {
+                    self.done = true;
+                } else {
+                    self.quit_armed = true;
+                    self.message =
+                        Some("unsaved changes: press q again to discard, s to save".to_string());
+                }
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.done = true;
+            }
+            KeyCode::Char('s') => {
+                if self.is_dirty() {
+                    self.view = View::Diff;
+                    self.diff_scroll = 0;
+                } else {
+                    self.message = Some("no changes to save".to_string());
+                }
+            }
+            KeyCode::Tab | KeyCode::Right | KeyCode::Left | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Rows if !self.options().is_empty() => Focus::Options,
+                    _ => Focus::Rows,
+                };
+                self.option = 0;
+            }
+            KeyCode::Up if shift => self.move_row(-1),
+            KeyCode::Down if shift => self.move_row(1),
+            KeyCode::Char('K') => self.move_row(-1),
+            KeyCode::Char('J') => self.move_row(1),
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::Char(' ') | KeyCode::Enter => match self.focus {
+                Focus::Rows => self.toggle_row(),
+                Focus::Options => self.activate_option(),
+            },
+            KeyCode::Backspace | KeyCode::Delete if self.focus == Focus::Options => {
+                if let Some(spec) = self.options().get(self.option).copied() {
+                    self.set(spec.key, None);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_diff_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => match self.save() {
+                Ok(()) => {
+                    self.view = View::Edit;
+                    self.message = Some(format!("saved {}", self.path.display()));
+                }
+                Err(e) => {
+                    self.view = View::Edit;
+                    self.message = Some(format!("cannot save {}: {}", self.path.display(), e));
+                }
+            },
+            KeyCode::Char('n') | KeyCode::Esc | KeyCode::Char('q') => self.view = View::Edit,
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.diff_scroll = self.diff_scroll.saturating_sub(1)
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.diff_scroll = self.diff_scroll.saturating_add(1)
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_edit_key(&mut self, key: KeyEvent) {
+        let Some(buffer) = self.editing.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => self.editing = None,
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Char(c) => buffer.push(c),
+            KeyCode::Enter => {
+                let text = self.editing.take().unwrap_or_default();
+                if let Some(spec) = self.options().get(self.option).copied() {
+                    self.commit_text(spec, text.trim());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn select(&mut self, delta: isize) {
+        match self.focus {
+            Focus::Rows => {
+                self.selected = step(self.selected, delta, self.rows.len());
+                self.option = 0;
+            }
+            Focus::Options => self.option = step(self.option, delta, self.options().len()),
+        }
+    }
+
+    /// 调整 segment 顺序，写入 segments.order
+    fn move_row(&mut self, delta: isize) {
+        if self.focus != Focus::Rows || self.row() == Row::General {
+            return;
+        }
+        let target = self.selected as isize + delta;
+        // 第 0 行是 General，segment 只能在 1..len 之间移动
+        if target < 1 || target >= self.rows.len() as isize {
+            return;
+        }
+        self.rows.swap(self.selected, target as usize);
+        self.selected = target as usize;
+
+        let order: Array = self
+            .rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Segment(name) => Some(*name),
+                Row::General => None,
+            })
+            .collect();
+        self.set("segments.order", Some(Value::Array(order)));
+    }
+
+    fn toggle_row(&mut self) {
+        match self.row() {
+            Row::General => {
+                self.focus = Focus::Options;
+                self.option = 0;
+            }
+            Row::Segment(name) => {
+                let enabled = self.is_enabled(name);
+                self.set(&format!("segments.{}", name), Some(Value::from(!enabled)));
+            }
+        }
+    }
+
+    fn activate_option(&mut self) {
+        let Some(spec) = self.options().get(self.option).copied() else {
+            return;
+        };
+        let current = self.value_text(&spec);
+        match spec.kind {
+            OptionKind::Bool => {
+                let enabled = current.as_deref() == Some("true");
+                self.set(spec.key, Some(Value::from(!enabled)));
+            }
+            OptionKind::Choice(choices) => {
+                let index = current
+                    .as_deref()
+                    .and_then(|c| choices.iter().position(|choice| *choice == c))
+                    .map_or(0, |i| (i + 1) % choices.len());
+                self.set(spec.key, Some(Value::from(choices[index])));
+            }
+            OptionKind::Integer | OptionKind::Float | OptionKind::Text => {
+                self.editing = Some(current.unwrap_or_default());
+            }
+        }
+    }
+
+    /// 提交输入的文本，空字符串表示删除该项
+    fn commit_text(&mut self, spec: OptionSpec, text: &str) {
+        if text.is_empty() {
+            self.set(spec.key, None);
+            return;
+        }
+        let value = match spec.kind {
+            OptionKind::Integer => text.parse::<i64>().map(Value::from).ok(),
+            OptionKind::Float => text.parse::<f64>().map(Value::from).ok(),
+            _ => Some(Value::from(text)),
+        };
+        match value {
+            Some(value) => self.set(spec.key, Some(value)),
+            None => self.message = Some(format!("{} is not a valid number", text)),
+        }
+    }
+
+    fn save(&mut self) -> io::Result<()> {
+        let content = self.doc.to_string();
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = self
+            .path
+            .with_extension(format!("toml.{}.tmp", std::process::id()));
+        fs::write(&tmp, &content)?;
+        fs::rename(&tmp, &self.path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp);
+        })?;
+        self.original = content;
+        Ok(())
+    }
+}
+
+fn step(index: usize, delta: isize, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    (index as isize + delta).clamp(0, len as isize - 1) as usize
+}
+
+/// 按点分路径写入值，缺失的中间表会被创建；路径上有非表的值时返回 false
+fn set_path(doc: &mut DocumentMut, key: &str, value: Option<Value>) -> bool {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let Some(last) = parts.pop() else {
+        return false;
+    };
+
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for part in parts {
+        if !table.contains_key(part) {
+            if value.is_none() {
+                return true;
+            }
+            table.insert(part, Item::Table(Table::new()));
+        }
+        match table.get_mut(part).and_then(Item::as_table_like_mut) {
+            Some(next) => table = next,
+            None => return false,
+        }
+    }
+
+    match value {
+        // 已有的值原地替换，保留键前的注释和行尾注释
+        Some(mut value) => match table.get_mut(last).and_then(Item::as_value_mut) {
+            Some(existing) => {
+                *value.decor_mut() = existing.decor().clone();
+                *existing = value;
+            }
+            None => {
+                table.insert(last, Item::Value(value));
+            }
+        },
+        None => {
+            table.remove(last);
+        }
+    }
+    true
+}
@@ -0,0 +1,33 @@
+mod app;
+mod view;
+
+use crate::config::ConfigLoader;
+use app::App;
+use crossterm::event::{self, Event, KeyEventKind};
+use std::error::Error;
+use std::path::PathBuf;
+
+/// 交互式编辑配置文件，config_path 为 None 时编辑 ~/.claude/ccline/config.toml
+pub fn run(config_path: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let path = match config_path {
+        Some(path) => PathBuf::from(path),
+        None => ConfigLoader::config_path().ok_or("cannot determine the home directory")?,
+    };
+    // 先解析配置，出错时直接报告而不是进入界面
+    let mut app = App::new(path)?;
+
+    let mut terminal = ratatui::init();
+    let result = (|| -> Result<(), Box<dyn Error>> {
+        while !app.done {
+            terminal.draw(|frame| view::draw(frame, &app))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+    result
+}
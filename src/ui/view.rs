@@ -0,0 +1,219 @@
+use super::app::{App, Focus, OptionKind, Row, View};
+use crate::core::output::{Color as SegmentColor, RenderedSegment, Style as SegmentStyle};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Frame;
+use similar::{ChangeTag, TextDiff};
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [preview, body, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(0),
+        Constraint::Length(2),
+    ])
+    .areas(frame.area());
+
+    draw_preview(frame, app, preview);
+    match app.view {
+        View::Edit => {
+            let [rows, options] =
+                Layout::horizontal([Constraint::Length(24), Constraint::Min(0)]).areas(body);
+            draw_rows(frame, app, rows);
+            draw_options(frame, app, options);
+        }
+        View::Diff => draw_diff(frame, app, body),
+    }
+    draw_footer(frame, app, footer);
+}
+
+fn draw_preview(frame: &mut Frame, app: &App, area: Rect) {
+    let line = preview_line(&app.preview, app.preview_separator);
+    let paragraph = Paragraph::new(line)
+        .block(Block::bordered().title(" Preview "))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+/// 把渲染结果转换成终端界面中的一行，分隔符与 statusline 一致
+fn preview_line(segments: &[RenderedSegment], separator: SegmentStyle) -> Line<'static> {
+    let mut spans = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(" | ", convert_style(separator)));
+        }
+        for span in &segment.spans {
+            spans.push(Span::styled(span.text.clone(), convert_style(span.style)));
+        }
+    }
+    if spans.is_empty() {
+        spans.push(Span::styled(
+            "(no segments enabled)",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn convert_style(style: SegmentStyle) -> Style {
+    let mut converted = Style::default();
+    if let Some(fg) = style.fg {
+        converted = converted.fg(convert_color(fg));
+    }
+    if style.bold {
+        converted = converted.add_modifier(Modifier::BOLD);
+    }
+    converted
+}
+
+fn convert_color(color: SegmentColor) -> Color {
+    match color {
+        SegmentColor::Black => Color::Black,
+        SegmentColor::Red => Color::Red,
+        SegmentColor::Green => Color::Green,
+        SegmentColor::Yellow => Color::Yellow,
+        SegmentColor::Blue => Color::Blue,
+        SegmentColor::Magenta => Color::Magenta,
+        SegmentColor::Cyan => Color::Cyan,
+        SegmentColor::White => Color::Gray,
+        SegmentColor::BrightBlack => Color::DarkGray,
+        SegmentColor::BrightRed => Color::LightRed,
+        SegmentColor::BrightGreen => Color::LightGreen,
+        SegmentColor::BrightYellow => Color::LightYellow,
+        SegmentColor::BrightBlue => Color::LightBlue,
+        SegmentColor::BrightMagenta => Color::LightMagenta,
+        SegmentColor::BrightCyan => Color::LightCyan,
+        SegmentColor::BrightWhite => Color::White,
+        SegmentColor::Fixed(n) => Color::Indexed(n),
+    }
+}
+
+fn focused_block(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(format!(" {} ", title));
+    if focused {
+        block.border_style(Style::default().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+fn draw_rows(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .rows
+        .iter()
+        .map(|row| match row {
+            Row::General => ListItem::new("    General"),
+            Row::Segment(name) => {
+                let mark = if app.is_enabled(name) { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {}", mark, name))
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .block(focused_block("Segments", app.focus == Focus::Rows))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_options(frame: &mut Frame, app: &App, area: Rect) {
+    let title = match app.row() {
+        Row::General => "General".to_string(),
+        Row::Segment(name) => format!("{} options", name),
+    };
+    let block = focused_block(&title, app.focus == Focus::Options);
+    let options = app.options();
+    if options.is_empty() {
+        let paragraph = Paragraph::new(Line::styled(
+            "This segment has no options.",
+            Style::default().fg(Color::DarkGray),
+        ))
+        .block(block);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let width = options
+        .iter()
+        .map(|spec| spec.label.len())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = options
+        .iter()
+        .enumerate()
+        .map(|(index, spec)| {
+            let editing = app.focus == Focus::Options && index == app.option;
+            let value = match (&app.editing, editing) {
+                (Some(buffer), true) => {
+                    Span::styled(format!("{}▏", buffer), Style::default().fg(Color::Yellow))
+                }
+                _ => match app.value_text(spec) {
+                    Some(value) => Span::raw(value),
+                    None => Span::styled("(unset)", Style::default().fg(Color::DarkGray)),
+                },
+            };
+            let hint = match spec.kind {
+                OptionKind::Choice(choices) => format!("  {}", choices.join("/")),
+                _ => String::new(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<width$}  ", spec.label, width = width)),
+                value,
+                Span::styled(hint, Style::default().fg(Color::DarkGray)),
+            ]))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let selected = (app.focus == Focus::Options).then_some(app.option);
+    let mut state = ListState::default().with_selected(selected);
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_diff(frame: &mut Frame, app: &App, area: Rect) {
+    let new_content = app.new_content();
+    let diff = TextDiff::from_lines(app.original(), new_content.as_str());
+    let mut lines = Vec::new();
+    for (index, group) in diff.grouped_ops(3).iter().enumerate() {
+        if index > 0 {
+            lines.push(Line::styled("…", Style::default().fg(Color::DarkGray)));
+        }
+        for op in group {
+            for change in diff.iter_changes(op) {
+                let (sign, style) = match change.tag() {
+                    ChangeTag::Delete => ("-", Style::default().fg(Color::Red)),
+                    ChangeTag::Insert => ("+", Style::default().fg(Color::Green)),
+                    ChangeTag::Equal => (" ", Style::default()),
+                };
+                let text = change.value().trim_end_matches('\n');
+                lines.push(Line::styled(format!("{} {}", sign, text), style));
+            }
+        }
+    }
+
+    let title = format!(" Save changes to {}? ", app.path.display());
+    let paragraph = Paragraph::new(lines)
+        .block(Block::bordered().title(title))
+        .scroll((app.diff_scroll, 0));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let help = match (app.view, &app.editing) {
+        (View::Diff, _) => "y/Enter save · n/Esc back · ↑↓ scroll",
+        (_, Some(_)) => "Enter apply · Esc cancel · empty value resets to default",
+        _ => "↑↓ select · Tab switch pane · Space toggle · Shift+↑↓ move · Del reset · s save · q quit",
+    };
+    let mut lines = vec![Line::styled(help, Style::default().fg(Color::DarkGray))];
+    if let Some(message) = &app.message {
+        lines.push(Line::styled(
+            message.clone(),
+            Style::default().fg(Color::Yellow),
+        ));
+    } else if app.is_dirty() {
+        lines.push(Line::styled("modified", Style::default().fg(Color::Yellow)));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+}